@@ -1,11 +1,26 @@
-use cosmwasm_schema::write_api;
+use std::env::current_dir;
+use std::fs::create_dir_all;
 
-use hackathon_movie_magic_contract::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use cosmwasm_schema::{export_schema, schema_for, write_api};
+
+use test_hackathon_contract::msg::{
+    AdventureStopResponse, EndGameResponse, ExecuteMsg, InitGameResponse, InstantiateMsg,
+    MigrateMsg, QueryMsg,
+};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
+        migrate: MigrateMsg,
     }
+
+    // Data returned by execute messages is not covered by write_api
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    export_schema(&schema_for!(InitGameResponse), &out_dir);
+    export_schema(&schema_for!(AdventureStopResponse), &out_dir);
+    export_schema(&schema_for!(EndGameResponse), &out_dir);
 }
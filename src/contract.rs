@@ -1,16 +1,45 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+    Storage,
+};
 use cw2::set_contract_version;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, GetCountResponse, InstantiateMsg, QueryMsg};
-use crate::state::{GameState, MovieMagicContractState, QueryResp, State, STATE};
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, LeaderboardSort, MigrateMsg, OptionTally, PausedResponse, QueryMsg,
+};
+use crate::state::{
+    Config, GameState, MovieMagicContractState, PlayerRating, PlayerStats, ScoringRule, StoryNode,
+    StoryTemplate, CONFIG, LEGACY_STATE, PLAYER_GAMES, PLAYER_STATS, RANKINGS, RATINGS,
+    SEASON_STATS, STATE,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:hackathon-movie-magic-contract";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// Hard cap on the players of a single game, keeping the gas used by each
+// message bounded
+pub const MAX_PLAYERS_PER_GAME: u32 = 50;
+const DEFAULT_MIN_PLAYERS: u32 = 2;
+// Cap on the entrants of a tournament, keeping the games created per round
+// bounded
+pub const MAX_TOURNAMENT_ENTRANTS: usize = 64;
+
+// Rating of players who have not finished a game yet
+pub const INITIAL_RATING: u32 = 1200;
+// Largest rating change a player can get from a single game
+const RATING_K: i64 = 32;
+// Expected score, in thousandths, of a player rated 0, 25, 50, ... 400
+// points above the opponent. Larger differences use the last entry.
+const EXPECTED_SCORE: [i64; 17] = [
+    500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909,
+];
+
 // #[cfg_attr(not(feature = "library"), entry_point)]
 // pub fn instantiate2(
 //     deps: DepsMut,
@@ -37,36 +66,162 @@ pub fn instantiate(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
-    msg: InstantiateMsg,
+    _msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     // let state = State {
     //     count: msg.count,
     //     owner: info.sender.clone(),
     // };
-    let state = MovieMagicContractState {
-        games: vec![],
-        owner: info.sender.clone(),
-    };
+    let state = MovieMagicContractState { games: vec![] };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
+    CONFIG.save(deps.storage, &default_config(info.sender.clone()))?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("owner", info.sender))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // Deployments from before the config kept the owner in the state
+    if CONFIG.may_load(deps.storage)?.is_none() {
+        let legacy = LEGACY_STATE.load(deps.storage)?;
+        CONFIG.save(deps.storage, &default_config(legacy.owner))?;
+    }
+    // Games stored before they had an id and a host are indexed by their
+    // position and hosted by their first player
+    let mut curr_games = STATE.load(deps.storage)?;
+    for (index, game) in curr_games.games.iter_mut().enumerate() {
+        game.id = index as u64;
+        if game.host.is_empty() {
+            game.host = game.players.first().cloned().unwrap_or_default();
+            game.current_adventure = game.adventure_winners.len() as u32;
+        }
+        for player in &game.players {
+            PLAYER_GAMES.save(deps.storage, (&Addr::unchecked(player), game.id), &())?;
+        }
+    }
+    STATE.save(deps.storage, &curr_games)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attribute("method", "migrate"))
+}
+
+fn default_config(owner: Addr) -> Config {
+    Config {
+        owner: Some(owner),
+        pending_owner: None,
+        fee_bps: 0,
+        max_players_per_game: MAX_PLAYERS_PER_GAME,
+        allowed_denoms: vec![],
+        guardian: None,
+        paused: false,
+        season_pool_bps: 0,
+        season_payouts: vec![],
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    // match msg {
-    //     QueryMsg::GetCount {} => to_binary(&query::count(deps)?),
-    // }
     // let resp = QueryResp {
     //     message: "Hello World".to_owned(),
     // };
 
-    let resp: MovieMagicContractState = STATE.load(deps.storage)?;
-
-    to_binary(&resp)
+    match msg {
+        QueryMsg::GetCount {} => {
+            let mut resp: MovieMagicContractState = STATE.load(deps.storage)?;
+            resp.games = resp.games.into_iter().map(query::public_game).collect();
+            to_binary(&resp)
+        }
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Paused {} => to_binary(&PausedResponse {
+            paused: CONFIG.load(deps.storage)?.paused,
+        }),
+        QueryMsg::Game { name } => to_binary(&query::public_game(query::game(deps, name)?)),
+        QueryMsg::Adventure {
+            name,
+            adventure_number,
+        } => to_binary(&query::adventure(deps, name, adventure_number)?),
+        QueryMsg::AdventureTally {
+            name,
+            adventure_number,
+        } => to_binary(&query::adventure_tally(deps, name, adventure_number)?),
+        QueryMsg::SimulatePayout {
+            name,
+            adventure_number,
+            player,
+            option,
+        } => to_binary(&query::simulate_payout(
+            deps,
+            name,
+            adventure_number,
+            player,
+            option,
+        )?),
+        QueryMsg::StoryPath { name } => to_binary(&query::story_path(deps, name)?),
+        QueryMsg::ListGames {
+            start_after,
+            limit,
+            include_private,
+            min_rating,
+            max_rating,
+        } => to_binary(&query::list_games(
+            deps,
+            start_after,
+            limit,
+            include_private.unwrap_or(false),
+            min_rating,
+            max_rating,
+        )?),
+        QueryMsg::Template {
+            template_id,
+            version,
+        } => to_binary(&query::template(deps, template_id, version)?),
+        QueryMsg::PlayerGames {
+            address,
+            start_after,
+            limit,
+            phase,
+        } => to_binary(&query::player_games(
+            deps,
+            address,
+            start_after,
+            limit,
+            phase,
+        )?),
+        QueryMsg::PlayerRating { address } => to_binary(&player_rating(
+            deps.storage,
+            &deps.api.addr_validate(&address)?,
+        )?),
+        QueryMsg::PlayerStats { address } => to_binary(&query::player_stats(deps, address)?),
+        QueryMsg::Leaderboard {
+            sort_by,
+            start_after,
+            limit,
+            season_id,
+        } => to_binary(&query::leaderboard(
+            deps,
+            sort_by.unwrap_or_default(),
+            start_after,
+            limit,
+            season_id,
+        )?),
+        QueryMsg::Season { season_id } => to_binary(&query::season(deps, season_id)?),
+        QueryMsg::Tournament { tournament_id } => {
+            to_binary(&query::tournament(deps, tournament_id)?)
+        }
+        QueryMsg::ListTournaments { start_after, limit } => {
+            to_binary(&query::list_tournaments(deps, start_after, limit)?)
+        }
+        QueryMsg::Claimable { address } => to_binary(&query::claimable(deps, address)?),
+        QueryMsg::ListSeasons { start_after, limit } => {
+            to_binary(&query::list_seasons(deps, start_after, limit)?)
+        }
+        QueryMsg::ListTemplates { start_after, limit } => {
+            to_binary(&query::list_templates(deps, start_after, limit)?)
+        }
+    }
 }
 
 // 2. Support the invocation message to create the game with atleast 1 player
@@ -80,55 +235,333 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
 // 6. Invocation to end the game
 
 #[entry_point]
-pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
     use ExecuteMsg::*;
 
-    match msg {
+    // Claims, refunds and admin actions keep working while the contract is
+    // paused, but nothing that computes or credits a payout does
+    let blocked_when_paused = matches!(
+        msg,
+        InitGame { .. }
+            | AddGamePlayer { .. }
+            | StartGame { .. }
+            | VoteForAdventure { .. }
+            | AbstainFromAdventure { .. }
+            | DelegateVote { .. }
+            | GameAdventureStop { .. }
+            | RevealAdventureAnswer { .. }
+            | EndGame { .. }
+            | CloseSeason { .. }
+            | CreateTournament { .. }
+            | JoinTournament { .. }
+    );
+    if blocked_when_paused && CONFIG.load(deps.storage)?.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    let response = match msg {
         InitGame {
             name,
             player,
             num_of_adventures,
             game_stake,
-        } => exec::init_game(deps, name, player, num_of_adventures, game_stake),
+            scoring_rule,
+            allow_vote_change,
+            story,
+            template_id,
+            template_version,
+            buy_in,
+            min_players,
+            max_players,
+            private_access,
+            teams,
+            team,
+        } => exec::init_game(
+            deps,
+            info,
+            name,
+            player,
+            num_of_adventures,
+            game_stake,
+            scoring_rule,
+            allow_vote_change,
+            story,
+            template_id,
+            template_version,
+            buy_in,
+            min_players.unwrap_or(DEFAULT_MIN_PLAYERS),
+            max_players,
+            private_access,
+            teams.unwrap_or_default(),
+            team,
+        ),
         AddGamePlayer {
             name,
             player,
             game_stake,
-        } => exec::add_player(deps, name, player, game_stake),
-        StartGame { name } => exec::start_game(deps, name),
+            join_code,
+            team,
+        } => exec::add_player(deps, info, name, player, game_stake, join_code, team),
+        StartGame { name } => exec::start_game(deps, env, name),
         VoteForAdventure {
             name,
             player,
             adventure_number,
             vote,
-        } => exec::vote_for_adventure(deps, name, player, vote, adventure_number),
+        } => exec::vote_for_adventure(deps, info, name, player, Some(vote), adventure_number),
+        AbstainFromAdventure {
+            name,
+            player,
+            adventure_number,
+        } => exec::vote_for_adventure(deps, info, name, player, None, adventure_number),
+        DelegateVote { name, to } => exec::delegate_vote(deps, info, name, to),
+        Undelegate { name } => exec::undelegate(deps, info, name),
+        OpenAdventure {
+            name,
+            adventure_number,
+            content,
+        } => exec::open_adventure(deps, info, name, adventure_number, content),
         GameAdventureStop {
             name,
             adventure_number,
-        } => exec::game_adventure_stop(deps, name, adventure_number),
-        EndGame { name } => exec::game_end(deps, name),
-    }
+        } => exec::game_adventure_stop(deps, info, name, adventure_number),
+        EndGame { name } => exec::game_end(deps, info, name),
+        CommitAdventureAnswer {
+            name,
+            adventure_number,
+            answer_hash,
+        } => exec::commit_adventure_answer(deps, info, name, adventure_number, answer_hash),
+        RevealAdventureAnswer {
+            name,
+            adventure_number,
+            option,
+            salt,
+        } => exec::reveal_adventure_answer(deps, info, name, adventure_number, option, salt),
+        ApproveTemplateCreator { address } => exec::set_template_creator(deps, info, address, true),
+        RevokeTemplateCreator { address } => exec::set_template_creator(deps, info, address, false),
+        PublishTemplate {
+            template_id,
+            title,
+            story,
+            num_of_adventures,
+            scoring_rule,
+            allow_vote_change,
+            buy_in,
+        } => exec::publish_template(
+            deps,
+            info,
+            template_id,
+            title,
+            StoryTemplate {
+                template_id: 0,
+                version: 0,
+                story,
+                num_of_adventures,
+                scoring_rule: scoring_rule.unwrap_or_default(),
+                allow_vote_change: allow_vote_change.unwrap_or(true),
+                buy_in,
+                deprecated: false,
+            },
+        ),
+        DeprecateTemplate {
+            template_id,
+            version,
+        } => exec::deprecate_template(deps, info, template_id, version),
+        ProposeOwner { new_owner } => exec::propose_owner(deps, info, new_owner),
+        AcceptOwnership {} => exec::accept_ownership(deps, info),
+        RenounceOwnership {} => exec::renounce_ownership(deps, info),
+        UpdateConfig {
+            fee_bps,
+            max_players_per_game,
+            allowed_denoms,
+            season_pool_bps,
+            season_payouts,
+        } => exec::update_config(
+            deps,
+            info,
+            fee_bps,
+            max_players_per_game,
+            allowed_denoms,
+            season_pool_bps,
+            season_payouts,
+        ),
+        RemovePlayer { name, player } => exec::remove_player(deps, info, name, player, false),
+        BanPlayer { name, player } => exec::remove_player(deps, info, name, player, true),
+        TransferHost { name, new_host } => exec::transfer_host(deps, info, name, new_host),
+        SetGuardian { guardian } => exec::set_guardian(deps, info, guardian),
+        Pause {} => exec::set_paused(deps, info, true),
+        Unpause {} => exec::set_paused(deps, info, false),
+        CreateSeason {
+            name,
+            start_time,
+            end_time,
+        } => exec::create_season(deps, env, info, name, start_time, end_time),
+        CloseSeason { season_id } => exec::close_season(deps, env, info, season_id),
+        Claim {} => exec::claim(deps, info),
+        CreateTournament {
+            name,
+            entry_fee,
+            game_size,
+            num_of_adventures,
+            scoring_rule,
+        } => exec::create_tournament(
+            deps,
+            info,
+            name,
+            entry_fee,
+            game_size,
+            num_of_adventures,
+            scoring_rule.unwrap_or_default(),
+        ),
+        JoinTournament { tournament_id } => exec::join_tournament(deps, info, tournament_id),
+        StartTournament { tournament_id } => exec::start_tournament(deps, info, tournament_id),
+        CancelTournament { tournament_id } => exec::cancel_tournament(deps, info, tournament_id),
+    }?;
+
+    Ok(response)
 }
 
 mod exec {
-    use std::collections::HashMap;
+    use cosmwasm_std::{BankMsg, Coin, Event, StdError, Timestamp, Uint128};
+    use sha2::{Digest, Sha256};
 
-    use cosmwasm_std::StdError;
+    use crate::msg::{AdventureStopResponse, EndGameResponse, InitGameResponse, PlayerStanding};
+    use crate::state::{
+        AdventureContent, PrivateGameAccess, ScoringRule, Season, StoryTemplateInfo, Tournament,
+        TournamentRound, ACTIVE_SEASON, CLAIMABLE, PLAYER_GAMES, SEASONS, SEASON_COUNT, TEMPLATES,
+        TEMPLATE_COUNT, TEMPLATE_CREATORS, TEMPLATE_VERSIONS, TOURNAMENTS, TOURNAMENT_COUNT,
+    };
 
     use super::*;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn init_game(
         deps: DepsMut,
+        info: MessageInfo,
         name: String,
         player: String,
-        num_of_adventures: u32,
+        num_of_adventures: Option<u32>,
         game_stake: u64,
+        scoring_rule: Option<ScoringRule>,
+        allow_vote_change: Option<bool>,
+        story: Option<Vec<StoryNode>>,
+        template_id: Option<u64>,
+        template_version: Option<u32>,
+        buy_in: Option<Coin>,
+        min_players: u32,
+        max_players: Option<u32>,
+        private_access: Option<PrivateGameAccess>,
+        teams: Vec<String>,
+        team: Option<String>,
     ) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        let max_players = max_players.unwrap_or(config.max_players_per_game);
+        // Settings given in the message take precedence over the template defaults
+        let template = match template_id {
+            Some(template_id) => Some(load_template(deps.as_ref(), template_id, template_version)?),
+            None => None,
+        };
+        if template.is_some() && story.is_some() {
+            return Err(StdError::generic_err(
+                "A game cannot have both a story and a template.",
+            ));
+        }
+        let num_of_adventures = num_of_adventures
+            .or_else(|| template.as_ref().map(|t| t.num_of_adventures))
+            .ok_or_else(|| StdError::generic_err("The number of adventures is required."))?;
+        let scoring_rule = scoring_rule
+            .or_else(|| template.as_ref().map(|t| t.scoring_rule))
+            .unwrap_or_default();
+        let allow_vote_change = allow_vote_change
+            .or_else(|| template.as_ref().map(|t| t.allow_vote_change))
+            .unwrap_or(true);
+        let story = story
+            .or_else(|| template.as_ref().map(|t| t.story.clone()))
+            .unwrap_or_default();
+        let buy_in = buy_in.or_else(|| template.as_ref().and_then(|t| t.buy_in.clone()));
+
+        // The host pays the buy-in like every other player
+        match &buy_in {
+            Some(buy_in) => {
+                validate_buy_in(buy_in)?;
+                if !config.allowed_denoms.is_empty()
+                    && !config.allowed_denoms.contains(&buy_in.denom)
+                {
+                    return Err(StdError::generic_err(format!(
+                        "Buy-ins in {} are not accepted.",
+                        buy_in.denom
+                    )));
+                }
+                ensure_buy_in_paid(&info, buy_in, game_stake)?;
+            }
+            None => ensure_no_funds(&info)?,
+        }
+
+        if num_of_adventures == 0 {
+            return Err(StdError::generic_err(
+                "A game needs at least one adventure.",
+            ));
+        }
+        if min_players == 0
+            || min_players > max_players
+            || max_players > config.max_players_per_game
+        {
+            return Err(StdError::generic_err(format!(
+                "Player limits must satisfy 1 <= min_players <= max_players <= {}.",
+                config.max_players_per_game
+            )));
+        }
+        validate_story(&story)?;
+        let private_access = match private_access {
+            Some(access) => {
+                if access.invite_list.is_empty() && access.join_code_hash.is_none() {
+                    return Err(StdError::generic_err(
+                        "A private game needs an invite list or a join code.",
+                    ));
+                }
+                let invite_list = access
+                    .invite_list
+                    .iter()
+                    .map(|address| Ok(deps.api.addr_validate(address)?.to_string()))
+                    .collect::<StdResult<_>>()?;
+                Some(PrivateGameAccess {
+                    invite_list,
+                    join_code_hash: access.join_code_hash.map(|hash| hash.to_lowercase()),
+                })
+            }
+            None => None,
+        };
+        if !teams.is_empty() {
+            let mut names = teams.clone();
+            names.sort();
+            names.dedup();
+            if teams.len() < 2 || names.len() != teams.len() || names.iter().any(String::is_empty) {
+                return Err(StdError::generic_err(
+                    "A team game needs at least two distinct, non-empty team names.",
+                ));
+            }
+        }
+        let host_team = validate_team(&teams, team)?;
+        // Story games start at the first node
+        let story_path = if story.is_empty() { vec![] } else { vec![0] };
+
         let mut curr_games = STATE.load(deps.storage)?;
+        // Games are looked up by name, so names must be unique
+        if curr_games.games.iter().any(|game| game.name == name) {
+            return Err(StdError::generic_err(
+                "A game with this name already exists.",
+            ));
+        }
         let mut games = curr_games.games;
+        let host = deps.api.addr_validate(&player)?.to_string();
         let new_game = GameState {
             name: name.clone(),
-            players: vec![deps.api.addr_validate(&player)?.to_string()],
+            players: vec![host.clone()],
             total_funds: game_stake,
             adventure_funds: 0,
             initiated: true,
@@ -137,43 +570,132 @@ mod exec {
             winner: "".to_string(),
             winning_reward: 0,
             adventure_votes: vec![],
-            num_of_adventures: num_of_adventures,
+            num_of_adventures,
             adventure_winners: vec![],
             adventure_rewards: vec![],
             adventure_winning_votes: vec![],
+            scoring_rule,
+            host: host.clone(),
+            adventure_answer_hashes: vec![None; num_of_adventures as usize],
+            adventure_answers: vec![None; num_of_adventures as usize],
+            allow_vote_change,
+            adventure_abstentions: vec![],
+            current_adventure: 0,
+            adventure_open: false,
+            adventure_contents: vec![],
+            adventure_winning_options: vec![],
+            story,
+            story_path,
+            template_id: template.as_ref().map(|t| t.template_id),
+            template_version: template.as_ref().map(|t| t.version),
+            buy_in,
+            min_players,
+            max_players,
+            private_access,
+            id: games.len() as u64,
+            player_stakes: HashMap::from([(host.clone(), game_stake)]),
+            banned_players: vec![],
+            fee_amount: 0,
+            season_id: None,
+            season_pool_amount: 0,
+            tournament_id: None,
+            teams,
+            player_teams: host_team
+                .map(|team| HashMap::from([(host, team)]))
+                .unwrap_or_default(),
+            delegations: HashMap::new(),
+        };
+        let event = game_event("game_created", &new_game)
+            .add_attribute("host", &new_game.host)
+            .add_attribute("stake", game_stake.to_string())
+            .add_attribute("num_of_adventures", num_of_adventures.to_string())
+            .add_attribute("scoring_rule", format!("{:?}", scoring_rule).to_lowercase());
+        let data = InitGameResponse {
+            game_id: new_game.id,
         };
+        PLAYER_GAMES.save(
+            deps.storage,
+            (&Addr::unchecked(&new_game.host), new_game.id),
+            &(),
+        )?;
 
         games.push(new_game);
         curr_games.games = games;
 
         STATE.save(deps.storage, &curr_games)?;
 
-        Ok(Response::new())
+        Ok(Response::new().add_event(event).set_data(to_binary(&data)?))
     }
 
     pub fn add_player(
         deps: DepsMut,
+        info: MessageInfo,
         name: String,
         player: String,
         game_stake: u64,
+        join_code: Option<String>,
+        team: Option<String>,
     ) -> StdResult<Response> {
         let mut curr_games = STATE.load(deps.storage)?;
         let game_find_result = curr_games.games.iter_mut().find(|game| game.name == name);
+        let mut response = Response::new();
 
         match game_find_result {
             Some(game) => {
+                // Stakes can only be refunded before the game starts
+                if game.started {
+                    return Err(StdError::generic_err("Game has already started."));
+                }
+                // Private games need an invitation or the join code
+                if let Some(access) = &game.private_access {
+                    let invited = access.invite_list.contains(&player);
+                    let code_matches = match (&access.join_code_hash, &join_code) {
+                        (Some(hash), Some(code)) => {
+                            hex::encode(Sha256::digest(code.as_bytes())) == *hash
+                        }
+                        _ => false,
+                    };
+                    if !invited && !code_matches {
+                        return Err(StdError::generic_err(
+                            "This game is private. An invitation or join code is required.",
+                        ));
+                    }
+                }
+                if game.players.len() >= game.max_players as usize {
+                    return Err(StdError::generic_err("This game is full."));
+                }
+                // Games with a fixed buy-in require the exact amount to be sent
+                match &game.buy_in {
+                    Some(buy_in) => ensure_buy_in_paid(&info, buy_in, game_stake)?,
+                    None => ensure_no_funds(&info)?,
+                }
+                let player = deps.api.addr_validate(&player)?.to_string();
+                if game.banned_players.contains(&player) {
+                    return Err(StdError::generic_err(
+                        "This player has been banned from the game.",
+                    ));
+                }
                 // Make sure the player is not already part of the game
-                // Disable the below check after testing
-                // if !game.players.contains(&player) {
-                //     return Err(StdError::generic_err(
-                //         "This player is already part of the game.",
-                //     ));
-                // }
+                if game.players.contains(&player) {
+                    return Err(StdError::generic_err(
+                        "This player is already part of the game.",
+                    ));
+                }
+                let team = validate_team(&game.teams, team)?;
                 // Add the player to the game
-                game.players
-                    .push(deps.api.addr_validate(&player)?.to_string());
+                game.players.push(player.clone());
                 // Add the funds from the player to the game
                 game.total_funds += game_stake;
+                game.player_stakes.insert(player.clone(), game_stake);
+                PLAYER_GAMES.save(deps.storage, (&Addr::unchecked(&player), game.id), &())?;
+                let mut event = game_event("player_joined", game)
+                    .add_attribute("player", &player)
+                    .add_attribute("stake", game_stake.to_string());
+                if let Some(team) = team {
+                    event = event.add_attribute("team", &team);
+                    game.player_teams.insert(player, team);
+                }
+                response = response.add_event(event);
             }
             None => {
                 return Err(StdError::generic_err("Game not found"));
@@ -182,11 +704,121 @@ mod exec {
 
         STATE.save(deps.storage, &curr_games)?;
 
-        Ok(Response::new())
+        Ok(response)
+    }
+
+    // Removes a player from a game that has not started yet, refunding their
+    // stake. When banning, the address is also prevented from joining again.
+    pub fn remove_player(
+        deps: DepsMut,
+        info: MessageInfo,
+        name: String,
+        player: String,
+        ban: bool,
+    ) -> StdResult<Response> {
+        let mut curr_games = STATE.load(deps.storage)?;
+
+        let game = curr_games
+            .games
+            .iter_mut()
+            .find(|game| game.name == name)
+            .ok_or_else(|| StdError::generic_err("Game not found"))?;
+
+        if info.sender != game.host {
+            return Err(StdError::generic_err(
+                "Only the host can manage the players of the game.",
+            ));
+        }
+        let player = deps.api.addr_validate(&player)?.to_string();
+        if player == game.host {
+            return Err(StdError::generic_err("The host cannot be removed."));
+        }
+
+        let mut response = Response::new();
+        if game.players.contains(&player) {
+            if game.started {
+                return Err(StdError::generic_err(
+                    "Players cannot be removed once the game has started.",
+                ));
+            }
+            game.players.retain(|p| *p != player);
+            game.player_teams.remove(&player);
+            game.delegations
+                .retain(|delegator, delegate| *delegator != player && *delegate != player);
+            PLAYER_GAMES.remove(deps.storage, (&Addr::unchecked(&player), game.id));
+            let stake = game.player_stakes.remove(&player).unwrap_or_default();
+            game.total_funds -= stake;
+            // Only games with a buy-in hold real funds to refund
+            if let Some(buy_in) = &game.buy_in {
+                response = response.add_message(BankMsg::Send {
+                    to_address: player.clone(),
+                    amount: vec![Coin {
+                        denom: buy_in.denom.clone(),
+                        amount: Uint128::from(stake),
+                    }],
+                });
+            }
+            response = response.add_event(
+                game_event("player_removed", game)
+                    .add_attribute("player", &player)
+                    .add_attribute("refund", stake.to_string()),
+            );
+        } else if !ban {
+            return Err(StdError::generic_err(
+                "This player is not part of the game.",
+            ));
+        }
+
+        if ban && !game.banned_players.contains(&player) {
+            game.banned_players.push(player.clone());
+            response = response
+                .add_event(game_event("player_banned", game).add_attribute("player", player));
+        }
+
+        STATE.save(deps.storage, &curr_games)?;
+
+        Ok(response)
+    }
+
+    pub fn transfer_host(
+        deps: DepsMut,
+        info: MessageInfo,
+        name: String,
+        new_host: String,
+    ) -> StdResult<Response> {
+        let mut curr_games = STATE.load(deps.storage)?;
+
+        let game = curr_games
+            .games
+            .iter_mut()
+            .find(|game| game.name == name)
+            .ok_or_else(|| StdError::generic_err("Game not found"))?;
+
+        if info.sender != game.host {
+            return Err(StdError::generic_err(
+                "Only the host can transfer the host role.",
+            ));
+        }
+        let new_host = deps.api.addr_validate(&new_host)?.to_string();
+        if !game.players.contains(&new_host) {
+            return Err(StdError::generic_err(
+                "The new host must be a player of the game.",
+            ));
+        }
+
+        let previous_host = std::mem::replace(&mut game.host, new_host);
+        let event = game_event("host_transferred", game)
+            .add_attribute("previous_host", previous_host)
+            .add_attribute("new_host", &game.host);
+
+        STATE.save(deps.storage, &curr_games)?;
+
+        Ok(Response::new().add_event(event))
     }
 
-    pub fn start_game(deps: DepsMut, name: String) -> StdResult<Response> {
+    pub fn start_game(deps: DepsMut, env: Env, name: String) -> StdResult<Response> {
         let mut curr_games: MovieMagicContractState = STATE.load(deps.storage)?;
+        let config = CONFIG.load(deps.storage)?;
 
         // Make sure there non zero about staked in the game pool
 
@@ -194,36 +826,88 @@ mod exec {
 
         match game_find_result {
             Some(game) => {
-                if (game.total_funds == 0) || (game.players.len() < 2) {
-                    return Err(StdError::generic_err(
-                        "Game cannot be started with less than 2 players or zero funds.",
-                    ));
+                // The fee and the season pool share are taken once per game
+                if game.started {
+                    return Err(StdError::generic_err("Game has already started."));
+                }
+                if (game.total_funds == 0) || (game.players.len() < game.min_players as usize) {
+                    Err(StdError::generic_err(format!(
+                        "Game cannot be started with less than {} players or zero funds.",
+                        game.min_players
+                    )))
                 } else {
                     game.started = true;
-                    game.adventure_funds = ((80 * game.total_funds) / 100) as u64;
+                    game.season_id = active_season(deps.storage, env.block.time)?;
+                    // The fee and the season pool share are taken before the
+                    // pot is split into rewards. Only buy-in games hold real
+                    // funds to add to the season pool.
+                    game.fee_amount = game.total_funds * config.fee_bps as u64 / 10_000;
+                    if let (Some(buy_in), Some(season_id)) = (&game.buy_in, game.season_id) {
+                        game.season_pool_amount =
+                            game.total_funds * config.season_pool_bps as u64 / 10_000;
+                        if game.season_pool_amount > 0 {
+                            let mut season = SEASONS.load(deps.storage, season_id)?;
+                            add_coin(
+                                &mut season.pool,
+                                &buy_in.denom,
+                                Uint128::from(game.season_pool_amount),
+                            );
+                            SEASONS.save(deps.storage, season_id, &season)?;
+                        }
+                    }
+                    game.adventure_funds = (80 * prize_pot(game)) / 100;
+
+                    let mut response = Response::new().add_event(
+                        game_event("game_started", game)
+                            .add_attribute("players", game.players.len().to_string())
+                            .add_attribute("total_funds", game.total_funds.to_string())
+                            .add_attribute("adventure_funds", game.adventure_funds.to_string())
+                            .add_attribute("fee", game.fee_amount.to_string())
+                            .add_attribute("season_pool", game.season_pool_amount.to_string()),
+                    );
+                    if let (Some(buy_in), Some(owner)) = (&game.buy_in, &config.owner) {
+                        if game.fee_amount > 0 {
+                            response = response.add_message(BankMsg::Send {
+                                to_address: owner.to_string(),
+                                amount: vec![Coin {
+                                    denom: buy_in.denom.clone(),
+                                    amount: Uint128::from(game.fee_amount),
+                                }],
+                            });
+                        }
+                    }
+
                     STATE.save(deps.storage, &curr_games)?;
-                    Ok(Response::new())
+                    Ok(response)
                 }
             }
-            None => {
-                return Err(StdError::generic_err("Game not found"));
-            }
+            None => Err(StdError::generic_err("Game not found")),
         }
     }
 
+    // A vote of None records that the player abstains from the adventure
     pub fn vote_for_adventure(
         deps: DepsMut,
+        info: MessageInfo,
         name: String,
         player: String,
-        vote: u32,
+        vote: Option<u32>,
         adventure_number: u32,
     ) -> StdResult<Response> {
         let mut curr_games = STATE.load(deps.storage)?;
 
         let game_find_result = curr_games.games.iter_mut().find(|game| game.name == name);
+        let mut response = Response::new();
 
         match game_find_result {
             Some(game) => {
+                // Players only choose for themselves, so nobody can lock in
+                // the choice of another player
+                if info.sender != player {
+                    return Err(StdError::generic_err(
+                        "Players can only vote for themselves.",
+                    ));
+                }
                 // Make sure the player is part of the game
                 if !game.players.contains(&player) {
                     return Err(StdError::generic_err(
@@ -234,19 +918,40 @@ mod exec {
                 if !game.started {
                     return Err(StdError::generic_err("Game has not been started yet."));
                 }
-                if (adventure_number < (game.adventure_votes.len() as u32)) {
-                    // Add the vote of the player to the adventure
-                    game.adventure_votes[adventure_number as usize].insert(player, vote);
-                } else if (adventure_number == (game.adventure_votes.len() as u32)) {
-                    let adventure_vote_hash_map: HashMap<String, u32> = HashMap::new();
-                    game.adventure_votes.push(adventure_vote_hash_map);
-                    // Add the vote of the player to the adventure
-                    game.adventure_votes[adventure_number as usize].insert(player, vote);
-                } else {
+                // Only the currently open adventure accepts votes
+                if !game.adventure_open || adventure_number != game.current_adventure {
                     return Err(StdError::generic_err(
-                        "Voting has not started for this adventure.",
+                        "This adventure is not open for voting.",
                     ));
                 }
+                if let Some(vote) = vote {
+                    ensure_valid_option(game, adventure_number, vote)?;
+                }
+
+                ensure_can_choose(game, adventure_number, &player)?;
+
+                let votes = &mut game.adventure_votes[adventure_number as usize];
+                let abstentions = &mut game.adventure_abstentions[adventure_number as usize];
+
+                // Record the latest choice of the player, replacing any earlier one
+                votes.remove(&player);
+                abstentions.retain(|abstainer| *abstainer != player);
+                match vote {
+                    // Add the vote of the player to the adventure
+                    Some(vote) => {
+                        votes.insert(player.clone(), vote);
+                    }
+                    None => abstentions.push(player.clone()),
+                }
+                response = response.add_event(
+                    game_event("vote_cast", game)
+                        .add_attribute("adventure", adventure_number.to_string())
+                        .add_attribute("player", player)
+                        .add_attribute(
+                            "vote",
+                            vote.map_or("abstain".to_string(), |vote| vote.to_string()),
+                        ),
+                );
             }
             None => {
                 return Err(StdError::generic_err("Game not found"));
@@ -255,133 +960,2322 @@ mod exec {
 
         STATE.save(deps.storage, &curr_games)?;
 
-        Ok(Response::new())
+        Ok(response)
     }
 
-    pub fn game_adventure_stop(
+    pub fn delegate_vote(
         deps: DepsMut,
+        info: MessageInfo,
         name: String,
-        adventure_stop_number: u32,
+        to: String,
     ) -> StdResult<Response> {
         let mut curr_games = STATE.load(deps.storage)?;
 
-        let game_find_result = curr_games.games.iter_mut().find(|game| game.name == name);
+        let game = curr_games
+            .games
+            .iter_mut()
+            .find(|game| game.name == name)
+            .ok_or_else(|| StdError::generic_err("Game not found"))?;
 
-        match game_find_result {
-            Some(game) => {
-                // Get the adventure votes
-                let adventure_votes = game.adventure_votes.get(adventure_stop_number as usize);
-                let adventure_votes_unwrapped = adventure_votes.unwrap();
-                // game.adventure_votes[adventure_stop_number as usize];
-
-                let mut votes_count_map: HashMap<u32, Vec<String>> = HashMap::new();
-
-                // Generate map to track who votes for given options
-                for (player, vote) in adventure_votes_unwrapped.iter() {
-                    if (votes_count_map.contains_key(vote)) {
-                        let vec_ref = votes_count_map.get_mut(vote);
-                        match vec_ref {
-                            Some(vec) => {
-                                vec.push(player.clone());
-                            }
-                            None => {
-                                return Err(StdError::generic_err("Error in counting votes"));
-                            }
-                        }
-                    } else {
-                        votes_count_map.insert(vote.clone(), vec![player.clone()]);
-                    }
-                }
+        let player = info.sender.to_string();
+        let delegate = deps.api.addr_validate(&to)?.to_string();
+        if !game.players.contains(&player) || !game.players.contains(&delegate) {
+            return Err(StdError::generic_err(
+                "Votes can only be delegated between players of the game.",
+            ));
+        }
+        if game.ended {
+            return Err(StdError::generic_err("Game has already ended."));
+        }
+        if player == delegate {
+            return Err(StdError::generic_err(
+                "Players cannot delegate their vote to themselves.",
+            ));
+        }
+        ensure_delegation_can_change(game)?;
+        // Delegations are a single hop so every delegated vote follows a
+        // vote that was actually cast
+        if game.delegations.contains_key(&delegate) {
+            return Err(StdError::generic_err(
+                "The delegate has delegated their own vote.",
+            ));
+        }
+        if game.delegations.values().any(|d| *d == player) {
+            return Err(StdError::generic_err(
+                "Votes delegated to this player cannot be delegated further.",
+            ));
+        }
+
+        game.delegations.insert(player.clone(), delegate.clone());
+        let event = game_event("vote_delegated", game)
+            .add_attribute("player", player)
+            .add_attribute("delegate", delegate);
+
+        STATE.save(deps.storage, &curr_games)?;
+
+        Ok(Response::new().add_event(event))
+    }
+
+    pub fn undelegate(deps: DepsMut, info: MessageInfo, name: String) -> StdResult<Response> {
+        let mut curr_games = STATE.load(deps.storage)?;
+
+        let game = curr_games
+            .games
+            .iter_mut()
+            .find(|game| game.name == name)
+            .ok_or_else(|| StdError::generic_err("Game not found"))?;
+
+        ensure_delegation_can_change(game)?;
+        let player = info.sender.to_string();
+        let delegate = game
+            .delegations
+            .remove(&player)
+            .ok_or_else(|| StdError::generic_err("This player has not delegated their vote."))?;
+        let event = game_event("vote_undelegated", game)
+            .add_attribute("player", player)
+            .add_attribute("delegate", delegate);
 
-                let mut vote_count_opt1: u32 = 0;
-                let mut vote_count_opt2: u32 = 0;
+        STATE.save(deps.storage, &curr_games)?;
+
+        Ok(Response::new().add_event(event))
+    }
+
+    // Changing a delegation while an adventure is open would change a vote
+    // that was already cast, which only some games allow
+    fn ensure_delegation_can_change(game: &GameState) -> StdResult<()> {
+        if game.adventure_open && !game.allow_vote_change {
+            return Err(StdError::generic_err(
+                "Delegations cannot change while an adventure is open in this game.",
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn open_adventure(
+        deps: DepsMut,
+        info: MessageInfo,
+        name: String,
+        adventure_number: u32,
+        content: Option<AdventureContent>,
+    ) -> StdResult<Response> {
+        let mut curr_games = STATE.load(deps.storage)?;
+
+        let game = curr_games
+            .games
+            .iter_mut()
+            .find(|game| game.name == name)
+            .ok_or_else(|| StdError::generic_err("Game not found"))?;
+
+        if info.sender != game.host {
+            return Err(StdError::generic_err("Only the host can open adventures."));
+        }
+        if let Some(content) = &content {
+            if content.option_labels.len() < 2 {
+                return Err(StdError::generic_err(
+                    "An adventure needs at least two options.",
+                ));
+            }
+        }
+        if !game.started || game.ended {
+            return Err(StdError::generic_err("Game is not in progress."));
+        }
+        if !game.story.is_empty() && game.story_path.len() <= adventure_number as usize {
+            return Err(StdError::generic_err(
+                "The story has no scene for this adventure.",
+            ));
+        }
+        if game.adventure_open {
+            return Err(StdError::generic_err(
+                "The current adventure has not been closed yet.",
+            ));
+        }
+        if adventure_number >= game.num_of_adventures {
+            return Err(StdError::generic_err(
+                "Adventure number exceeds the number of adventures in the game.",
+            ));
+        }
+        if adventure_number != game.current_adventure {
+            return Err(StdError::generic_err("Adventures must be opened in order."));
+        }
 
-                // Count the number of votes given to each candidate option
-                for (vote, players) in votes_count_map.iter() {
-                    if (*vote == 1) {
-                        vote_count_opt1 = players.len() as u32;
-                    } else if (*vote == 2) {
-                        vote_count_opt2 = players.len() as u32;
+        // Story scenes carry their own content unless the host overrides it
+        let content = match story_node(game, adventure_number) {
+            Some(node) => {
+                if let Some(content) = &content {
+                    if content.option_labels.len() != node.next.len() {
+                        return Err(StdError::generic_err(
+                            "Option labels must match the options of the story node.",
+                        ));
                     }
                 }
+                content.or_else(|| node.content.clone())
+            }
+            None => content,
+        };
 
-                if (vote_count_opt1 > vote_count_opt2) {
-                    // Calculate the reward amount for each player
-                    let reward_amound = ((game.adventure_funds / (game.num_of_adventures as u64))
-                        / (vote_count_opt1 as u64)) as u64;
-                    game.adventure_winning_votes.push(vote_count_opt1);
-                    game.adventure_winners
-                        .push(votes_count_map.get(&1).unwrap().clone());
-                    game.adventure_rewards.push(reward_amound);
-                } else {
-                    // Calculate the reward amount for each player
-                    let reward_amound = ((game.adventure_funds / (game.num_of_adventures as u64))
-                        / (vote_count_opt2 as u64)) as u64;
-                    game.adventure_winning_votes.push(vote_count_opt2);
-                    game.adventure_winners
-                        .push(votes_count_map.get(&2).unwrap().clone());
-                    game.adventure_rewards.push(reward_amound);
+        game.adventure_votes.push(HashMap::new());
+        // Games created before abstentions were tracked have no entries yet
+        game.adventure_abstentions
+            .resize(game.adventure_votes.len(), vec![]);
+        game.adventure_contents
+            .resize(game.adventure_votes.len() - 1, None);
+        game.adventure_contents.push(content);
+        game.adventure_open = true;
+        // An answer recorded before the scene was known must be one of its options
+        if let Some(Some(answer)) = game.adventure_answers.get(adventure_number as usize) {
+            ensure_valid_option(game, adventure_number, *answer)?;
+        }
+        let event = game_event("adventure_opened", game)
+            .add_attribute("adventure", adventure_number.to_string());
+
+        STATE.save(deps.storage, &curr_games)?;
+
+        Ok(Response::new().add_event(event))
+    }
+
+    pub fn game_adventure_stop(
+        deps: DepsMut,
+        info: MessageInfo,
+        name: String,
+        adventure_stop_number: u32,
+    ) -> StdResult<Response> {
+        let mut curr_games = STATE.load(deps.storage)?;
+
+        let game = curr_games
+            .games
+            .iter_mut()
+            .find(|game| game.name == name)
+            .ok_or_else(|| StdError::generic_err("Game not found"))?;
+
+        if info.sender != game.host {
+            return Err(StdError::generic_err("Only the host can close adventures."));
+        }
+        // Adventures are closed once each, in the order they were opened
+        if adventure_stop_number < game.current_adventure {
+            return Err(StdError::generic_err(
+                "This adventure has already been closed.",
+            ));
+        }
+        if !game.adventure_open || adventure_stop_number != game.current_adventure {
+            return Err(StdError::generic_err("This adventure is not open."));
+        }
+        // Revealing the answer is what closes a host-judged adventure
+        if game.scoring_rule == ScoringRule::HostJudged {
+            return Err(StdError::generic_err(
+                "Host-judged adventures are closed by revealing the answer.",
+            ));
+        }
+
+        let game_id = game.id;
+        let response = close_adventure(
+            deps.storage,
+            &mut curr_games.games,
+            game_id,
+            Response::new(),
+        )?;
+
+        STATE.save(deps.storage, &curr_games)?;
+
+        Ok(response)
+    }
+
+    // Closes the open adventure of the game and records its winners. In a
+    // story game the winning option picks the next scene, and reaching a
+    // terminal scene ends the game and advances its tournament.
+    fn close_adventure(
+        storage: &mut dyn Storage,
+        games: &mut Vec<GameState>,
+        game_id: u64,
+        mut response: Response,
+    ) -> StdResult<Response> {
+        let game = &mut games[game_id as usize];
+        let adventure_stop_number = game.current_adventure;
+
+        // Get the adventure votes
+        let adventure_votes = &game.adventure_votes[adventure_stop_number as usize];
+
+        // An adventure nobody voted in is void: it has no winning option and
+        // pays no reward
+        let AdventureOutcome {
+            winning_option,
+            winners: winning_players,
+            winning_vote_count,
+            reward: reward_amount,
+        } = adventure_outcome(game, adventure_stop_number, adventure_votes).unwrap_or(
+            AdventureOutcome {
+                winning_option: 0,
+                winners: vec![],
+                winning_vote_count: 0,
+                reward: 0,
+            },
+        );
+        let mut data = AdventureStopResponse {
+            adventure_number: adventure_stop_number,
+            tally: option_tally(game, adventure_stop_number, adventure_votes),
+            winning_option,
+            winners: winning_players.clone(),
+            reward: reward_amount,
+            game_result: None,
+        };
+        let mut event = game_event("adventure_closed", game)
+            .add_attribute("adventure", adventure_stop_number.to_string())
+            .add_attribute("winning_option", winning_option.to_string())
+            .add_attribute("reward", reward_amount.to_string());
+        // Attribute values cannot be empty
+        if !winning_players.is_empty() {
+            event = event.add_attribute("winners", winning_players.join(","));
+        }
+        response = response.add_event(event);
+        game.adventure_winning_votes.push(winning_vote_count);
+        game.adventure_winners.push(winning_players);
+        game.adventure_rewards.push(reward_amount);
+        game.adventure_winning_options
+            .resize(adventure_stop_number as usize, 0);
+        game.adventure_winning_options.push(winning_option);
+
+        // Move the cursor to the next adventure
+        game.adventure_open = false;
+        game.current_adventure += 1;
+
+        // A void adventure follows the first option
+        let mut ended = false;
+        if let Some(node) = story_node(game, adventure_stop_number) {
+            let next = node
+                .next
+                .get(winning_option.saturating_sub(1) as usize)
+                .ok_or_else(|| {
+                    StdError::generic_err("This option does not exist for the adventure.")
+                })?;
+            match *next {
+                Some(next_node) => game.story_path.push(next_node),
+                None => {
+                    response = response.add_event(finish_game(storage, game)?);
+                    data.game_result = Some(end_game_response(game));
+                    ended = true;
                 }
             }
-            None => {
-                return Err(StdError::generic_err("Game not found"));
-            }
         }
 
-        STATE.save(deps.storage, &curr_games)?;
+        if ended {
+            response = response.add_events(advance_tournament(storage, games, game_id)?);
+        }
 
-        Ok(Response::new())
+        Ok(response.set_data(to_binary(&data)?))
     }
 
-    pub fn game_end(deps: DepsMut, name: String) -> StdResult<Response> {
+    pub fn game_end(deps: DepsMut, info: MessageInfo, name: String) -> StdResult<Response> {
         let mut curr_games = STATE.load(deps.storage)?;
 
         let game_find_result = curr_games.games.iter_mut().find(|game| game.name == name);
+        let mut response = Response::new();
+        let game_id;
 
         match game_find_result {
             Some(game) => {
+                if info.sender != game.host {
+                    return Err(StdError::generic_err("Only the host can end the game."));
+                }
                 // Make sure the game has been started
                 if !game.started {
                     return Err(StdError::generic_err("Game has not been started yet."));
                 }
-
-                let advernture_winners_for_adventure = game.adventure_winners.clone();
-
-                // Compute the overall winner of the game
-
-                // 1. Flatten the adventure winners
-                let winners_across_adventures: Vec<String> = advernture_winners_for_adventure
-                    .into_iter()
-                    .flatten()
-                    .collect();
-
-                // 2. Create hash map of each player and their winning count
-                let mut player_winning_count: HashMap<String, u32> = HashMap::new();
-                for player in winners_across_adventures {
-                    *player_winning_count.entry(player).or_default() += 1;
+                // Story games end on their own when an ending is reached
+                if game.ended {
+                    return Err(StdError::generic_err("Game has already ended."));
+                }
+                if game.adventure_open {
+                    return Err(StdError::generic_err(
+                        "The current adventure has not been closed yet.",
+                    ));
+                }
+                if game.current_adventure < game.num_of_adventures {
+                    return Err(StdError::generic_err(
+                        "Every adventure must be played before the game ends.",
+                    ));
+                }
+                // The winner of a tournament game is the one who advances
+                if game.tournament_id.is_some() && game.adventure_winners.iter().all(Vec::is_empty)
+                {
+                    return Err(StdError::generic_err(
+                        "A tournament game can only end once an adventure has a winner.",
+                    ));
                 }
 
-                // 3. Get the player with the highest winning count
-                let winning_player = (player_winning_count
-                    .into_iter()
-                    .max_by_key(|(_, v)| *v)
-                    .map(|(k, _)| k))
-                .unwrap();
-
-                game.winner = winning_player;
-
-                game.winning_reward = ((20 * game.total_funds) / 100) as u64;
-
-                // Mark the game as ended
-                game.ended = true;
+                response = response
+                    .add_event(finish_game(deps.storage, game)?)
+                    .set_data(to_binary(&end_game_response(game))?);
+                game_id = game.id;
             }
             None => {
                 return Err(StdError::generic_err("Game not found"));
             }
         }
+        response = response.add_events(advance_tournament(
+            deps.storage,
+            &mut curr_games.games,
+            game_id,
+        )?);
 
         STATE.save(deps.storage, &curr_games)?;
 
-        Ok(Response::new())
+        Ok(response)
+    }
+
+    pub fn propose_owner(
+        deps: DepsMut,
+        info: MessageInfo,
+        new_owner: String,
+    ) -> StdResult<Response> {
+        let mut config = ensure_owner(deps.as_ref(), &info)?;
+        config.pending_owner = Some(deps.api.addr_validate(&new_owner)?);
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "propose_owner")
+            .add_attribute("pending_owner", new_owner))
+    }
+
+    pub fn accept_ownership(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+        let mut config = CONFIG.load(deps.storage)?;
+        if config.pending_owner.as_ref() != Some(&info.sender) {
+            return Err(StdError::generic_err(
+                "Only the pending owner can accept ownership.",
+            ));
+        }
+        config.owner = config.pending_owner.take();
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "accept_ownership")
+            .add_attribute("owner", info.sender))
+    }
+
+    pub fn renounce_ownership(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+        let mut config = ensure_owner(deps.as_ref(), &info)?;
+        config.owner = None;
+        config.pending_owner = None;
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new().add_attribute("method", "renounce_ownership"))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_config(
+        deps: DepsMut,
+        info: MessageInfo,
+        fee_bps: Option<u16>,
+        max_players_per_game: Option<u32>,
+        allowed_denoms: Option<Vec<String>>,
+        season_pool_bps: Option<u16>,
+        season_payouts: Option<Vec<u16>>,
+    ) -> StdResult<Response> {
+        let mut config = ensure_owner(deps.as_ref(), &info)?;
+
+        if let Some(fee_bps) = fee_bps {
+            config.fee_bps = fee_bps;
+        }
+        if let Some(season_pool_bps) = season_pool_bps {
+            config.season_pool_bps = season_pool_bps;
+        }
+        if config.fee_bps as u32 + config.season_pool_bps as u32 > 10_000 {
+            return Err(StdError::generic_err(
+                "The fee and the season pool cannot exceed 10000 bps.",
+            ));
+        }
+        if let Some(season_payouts) = season_payouts {
+            if season_payouts.iter().map(|bps| *bps as u32).sum::<u32>() > 10_000 {
+                return Err(StdError::generic_err(
+                    "The season payouts cannot exceed 10000 bps.",
+                ));
+            }
+            config.season_payouts = season_payouts;
+        }
+        if let Some(max_players_per_game) = max_players_per_game {
+            if max_players_per_game == 0 || max_players_per_game > MAX_PLAYERS_PER_GAME {
+                return Err(StdError::generic_err(format!(
+                    "The player cap must be between 1 and {}.",
+                    MAX_PLAYERS_PER_GAME
+                )));
+            }
+            config.max_players_per_game = max_players_per_game;
+        }
+        if let Some(allowed_denoms) = allowed_denoms {
+            config.allowed_denoms = allowed_denoms;
+        }
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new().add_attribute("method", "update_config"))
+    }
+
+    pub fn set_guardian(
+        deps: DepsMut,
+        info: MessageInfo,
+        guardian: Option<String>,
+    ) -> StdResult<Response> {
+        let mut config = ensure_owner(deps.as_ref(), &info)?;
+        config.guardian = guardian
+            .map(|guardian| deps.api.addr_validate(&guardian))
+            .transpose()?;
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new().add_attribute("method", "set_guardian"))
+    }
+
+    pub fn set_paused(deps: DepsMut, info: MessageInfo, paused: bool) -> StdResult<Response> {
+        let mut config = CONFIG.load(deps.storage)?;
+        let sender = Some(&info.sender);
+        if config.owner.as_ref() != sender && config.guardian.as_ref() != sender {
+            return Err(StdError::generic_err(
+                "Only the owner or the guardian can pause the contract.",
+            ));
+        }
+        config.paused = paused;
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "set_paused")
+            .add_attribute("paused", paused.to_string()))
+    }
+
+    // Schedules a new season after the current one
+    pub fn create_season(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        name: String,
+        start_time: Timestamp,
+        end_time: Timestamp,
+    ) -> StdResult<Response> {
+        ensure_owner(deps.as_ref(), &info)?;
+
+        if end_time <= start_time {
+            return Err(StdError::generic_err("A season must end after it starts."));
+        }
+        if let Some(latest) = ACTIVE_SEASON.may_load(deps.storage)?.flatten() {
+            let latest = SEASONS.load(deps.storage, latest)?;
+            if start_time < latest.end_time {
+                return Err(StdError::generic_err(
+                    "The season overlaps with another season.",
+                ));
+            }
+            if env.block.time < latest.end_time {
+                return Err(StdError::generic_err(
+                    "The next season can only be created once the current one has ended.",
+                ));
+            }
+        }
+
+        let id = SEASON_COUNT.may_load(deps.storage)?.unwrap_or_default();
+        SEASON_COUNT.save(deps.storage, &(id + 1))?;
+        SEASONS.save(
+            deps.storage,
+            id,
+            &Season {
+                id,
+                name,
+                start_time,
+                end_time,
+                closed: false,
+                pool: vec![],
+            },
+        )?;
+        ACTIVE_SEASON.save(deps.storage, &Some(id))?;
+
+        Ok(Response::new()
+            .add_attribute("method", "create_season")
+            .add_attribute("season_id", id.to_string()))
+    }
+
+    pub fn close_season(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        season_id: u64,
+    ) -> StdResult<Response> {
+        // Once ownership has been renounced anyone can close an ended season
+        let config = CONFIG.load(deps.storage)?;
+        if config.owner.is_some() {
+            ensure_owner(deps.as_ref(), &info)?;
+        }
+
+        let mut season = SEASONS
+            .may_load(deps.storage, season_id)?
+            .ok_or_else(|| StdError::generic_err("Season not found"))?;
+        if season.closed {
+            return Err(StdError::generic_err("Season has already been closed."));
+        }
+        if env.block.time < season.end_time {
+            return Err(StdError::generic_err("Season has not ended yet."));
+        }
+
+        // The season stats stop changing once the season is closed, so its
+        // ranking by wins is final
+        let board = ranking_board(LeaderboardSort::Wins, Some(season_id));
+        let top_players = RANKINGS
+            .sub_prefix(&board)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .take(config.season_payouts.len().max(1))
+            .map(|key| key.map(|(_, address)| address))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        // Credit the prize pool to the top players following the payout
+        // table, and what is left to the owner, or to the top player once
+        // ownership has been renounced
+        let remainder_recipient = config.owner.as_ref().or(top_players.first());
+        for coin in &season.pool {
+            let mut remaining = coin.amount;
+            for (address, bps) in top_players.iter().zip(&config.season_payouts) {
+                let prize = coin.amount.multiply_ratio(*bps as u128, 10_000u128);
+                credit_claimable(deps.storage, address, &coin.denom, prize)?;
+                remaining -= prize;
+            }
+            if let Some(recipient) = remainder_recipient {
+                credit_claimable(deps.storage, recipient, &coin.denom, remaining)?;
+            }
+        }
+
+        season.closed = true;
+        SEASONS.save(deps.storage, season_id, &season)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "close_season")
+            .add_attribute("season_id", season_id.to_string()))
+    }
+
+    pub fn claim(deps: DepsMut, info: MessageInfo) -> StdResult<Response> {
+        let balances = CLAIMABLE
+            .prefix(&info.sender)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        if balances.is_empty() {
+            return Err(StdError::generic_err("Nothing to claim."));
+        }
+
+        let mut amount = vec![];
+        for (denom, balance) in balances {
+            CLAIMABLE.remove(deps.storage, (&info.sender, &denom));
+            amount.push(Coin {
+                denom,
+                amount: balance,
+            });
+        }
+
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount,
+            })
+            .add_attribute("method", "claim")
+            .add_attribute("address", info.sender))
+    }
+
+    fn credit_claimable(
+        storage: &mut dyn Storage,
+        address: &Addr,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<()> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        CLAIMABLE.update(storage, (address, denom), |balance| {
+            Ok::<_, StdError>(balance.unwrap_or_default() + amount)
+        })?;
+        Ok(())
+    }
+
+    fn add_coin(coins: &mut Vec<Coin>, denom: &str, amount: Uint128) {
+        match coins.iter_mut().find(|coin| coin.denom == denom) {
+            Some(coin) => coin.amount += amount,
+            None => coins.push(Coin {
+                denom: denom.to_string(),
+                amount,
+            }),
+        }
+    }
+
+    // Part of the pot left for the rewards once the fee and the season pool
+    // share have been taken
+    fn prize_pot(game: &GameState) -> u64 {
+        game.total_funds - game.fee_amount - game.season_pool_amount
+    }
+
+    pub fn create_tournament(
+        deps: DepsMut,
+        info: MessageInfo,
+        name: String,
+        entry_fee: Option<Coin>,
+        game_size: u32,
+        num_of_adventures: u32,
+        scoring_rule: ScoringRule,
+    ) -> StdResult<Response> {
+        let config = CONFIG.load(deps.storage)?;
+        if game_size < 2 || game_size > config.max_players_per_game {
+            return Err(StdError::generic_err(format!(
+                "Tournament games must have between 2 and {} players.",
+                config.max_players_per_game
+            )));
+        }
+        if num_of_adventures == 0 {
+            return Err(StdError::generic_err(
+                "A game needs at least one adventure.",
+            ));
+        }
+        if let Some(entry_fee) = &entry_fee {
+            validate_buy_in(entry_fee)?;
+            if !config.allowed_denoms.is_empty()
+                && !config.allowed_denoms.contains(&entry_fee.denom)
+            {
+                return Err(StdError::generic_err(format!(
+                    "Buy-ins in {} are not accepted.",
+                    entry_fee.denom
+                )));
+            }
+        }
+
+        let id = TOURNAMENT_COUNT.may_load(deps.storage)?.unwrap_or_default();
+        TOURNAMENT_COUNT.save(deps.storage, &(id + 1))?;
+        TOURNAMENTS.save(
+            deps.storage,
+            id,
+            &Tournament {
+                id,
+                name,
+                organizer: info.sender.clone(),
+                entry_fee,
+                game_size,
+                num_of_adventures,
+                scoring_rule,
+                entrants: vec![],
+                rounds: vec![],
+                started: false,
+                winner: None,
+                cancelled: false,
+            },
+        )?;
+
+        Ok(Response::new().add_event(
+            tournament_event("tournament_created", id).add_attribute("organizer", info.sender),
+        ))
+    }
+
+    pub fn join_tournament(
+        deps: DepsMut,
+        info: MessageInfo,
+        tournament_id: u64,
+    ) -> StdResult<Response> {
+        let mut tournament = load_tournament(deps.as_ref(), tournament_id)?;
+        if tournament.started {
+            return Err(StdError::generic_err("Tournament has already started."));
+        }
+        if tournament.cancelled {
+            return Err(StdError::generic_err("Tournament has been cancelled."));
+        }
+        if tournament.entrants.len() >= MAX_TOURNAMENT_ENTRANTS {
+            return Err(StdError::generic_err("This tournament is full."));
+        }
+        let entrant = info.sender.to_string();
+        if tournament.entrants.contains(&entrant) {
+            return Err(StdError::generic_err(
+                "This player has already entered the tournament.",
+            ));
+        }
+        match &tournament.entry_fee {
+            Some(entry_fee) if info.funds != [entry_fee.clone()] => {
+                return Err(StdError::generic_err(format!(
+                    "This tournament requires an entry fee of exactly {}.",
+                    entry_fee
+                )));
+            }
+            Some(_) => {}
+            None if !info.funds.is_empty() => {
+                return Err(StdError::generic_err("This tournament has no entry fee."));
+            }
+            None => {}
+        }
+
+        tournament.entrants.push(entrant.clone());
+        TOURNAMENTS.save(deps.storage, tournament_id, &tournament)?;
+
+        Ok(Response::new().add_event(
+            tournament_event("tournament_joined", tournament_id).add_attribute("player", entrant),
+        ))
+    }
+
+    pub fn start_tournament(
+        deps: DepsMut,
+        info: MessageInfo,
+        tournament_id: u64,
+    ) -> StdResult<Response> {
+        let mut tournament = load_tournament(deps.as_ref(), tournament_id)?;
+        if info.sender != tournament.organizer {
+            return Err(StdError::generic_err(
+                "Only the organizer can start the tournament.",
+            ));
+        }
+        if tournament.started {
+            return Err(StdError::generic_err("Tournament has already started."));
+        }
+        if tournament.cancelled {
+            return Err(StdError::generic_err("Tournament has been cancelled."));
+        }
+        if tournament.entrants.len() < 2 {
+            return Err(StdError::generic_err(
+                "A tournament needs at least 2 entrants.",
+            ));
+        }
+
+        let mut curr_games = STATE.load(deps.storage)?;
+        tournament.started = true;
+        let entrants = tournament.entrants.clone();
+        let event = start_round(
+            deps.storage,
+            &mut curr_games.games,
+            &mut tournament,
+            entrants,
+        )?;
+        TOURNAMENTS.save(deps.storage, tournament_id, &tournament)?;
+        STATE.save(deps.storage, &curr_games)?;
+
+        Ok(Response::new().add_event(event))
+    }
+
+    pub fn cancel_tournament(
+        deps: DepsMut,
+        info: MessageInfo,
+        tournament_id: u64,
+    ) -> StdResult<Response> {
+        let mut tournament = load_tournament(deps.as_ref(), tournament_id)?;
+        let is_owner = CONFIG.load(deps.storage)?.owner.as_ref() == Some(&info.sender);
+        if info.sender != tournament.organizer && !is_owner {
+            return Err(StdError::generic_err(
+                "Only the organizer or the owner can cancel the tournament.",
+            ));
+        }
+        if tournament.started {
+            return Err(StdError::generic_err("Tournament has already started."));
+        }
+        if tournament.cancelled {
+            return Err(StdError::generic_err("Tournament has been cancelled."));
+        }
+
+        if let Some(entry_fee) = &tournament.entry_fee {
+            for entrant in &tournament.entrants {
+                credit_claimable(
+                    deps.storage,
+                    &Addr::unchecked(entrant),
+                    &entry_fee.denom,
+                    entry_fee.amount,
+                )?;
+            }
+        }
+        tournament.cancelled = true;
+        TOURNAMENTS.save(deps.storage, tournament_id, &tournament)?;
+
+        Ok(Response::new().add_event(
+            tournament_event("tournament_cancelled", tournament_id)
+                .add_attribute("refunded", tournament.entrants.len().to_string()),
+        ))
+    }
+
+    // Splits the players into the games of a new round. A player left alone
+    // in the last group gets a bye.
+    fn start_round(
+        storage: &mut dyn Storage,
+        games: &mut Vec<GameState>,
+        tournament: &mut Tournament,
+        players: Vec<String>,
+    ) -> StdResult<Event> {
+        let round_number = tournament.rounds.len() + 1;
+        let mut round = TournamentRound {
+            games: vec![],
+            byes: vec![],
+        };
+        for (index, group) in players.chunks(tournament.game_size as usize).enumerate() {
+            if group.len() == 1 {
+                round.byes.extend_from_slice(group);
+                continue;
+            }
+            let id = games.len() as u64;
+            let num_of_adventures = tournament.num_of_adventures;
+            // A player may have taken the name already, in which case a
+            // suffix keeps the game name unique
+            let base_name = format!(
+                "{} round {} game {}",
+                tournament.name,
+                round_number,
+                index + 1
+            );
+            let mut name = base_name.clone();
+            let mut suffix = id;
+            while games.iter().any(|game| game.name == name) {
+                name = format!("{} #{}", base_name, suffix);
+                suffix += 1;
+            }
+            let game = GameState {
+                name,
+                players: group.to_vec(),
+                total_funds: 0,
+                adventure_funds: 0,
+                initiated: true,
+                started: true,
+                ended: false,
+                winner: "".to_string(),
+                winning_reward: 0,
+                adventure_votes: vec![],
+                num_of_adventures,
+                adventure_winners: vec![],
+                adventure_rewards: vec![],
+                adventure_winning_votes: vec![],
+                scoring_rule: tournament.scoring_rule,
+                host: tournament.organizer.to_string(),
+                adventure_answer_hashes: vec![None; num_of_adventures as usize],
+                adventure_answers: vec![None; num_of_adventures as usize],
+                allow_vote_change: true,
+                adventure_abstentions: vec![],
+                current_adventure: 0,
+                adventure_open: false,
+                adventure_contents: vec![],
+                adventure_winning_options: vec![],
+                story: vec![],
+                story_path: vec![],
+                template_id: None,
+                template_version: None,
+                buy_in: None,
+                min_players: group.len() as u32,
+                max_players: group.len() as u32,
+                private_access: None,
+                id,
+                player_stakes: group.iter().map(|player| (player.clone(), 0)).collect(),
+                banned_players: vec![],
+                fee_amount: 0,
+                season_id: None,
+                season_pool_amount: 0,
+                tournament_id: Some(tournament.id),
+                teams: vec![],
+                player_teams: HashMap::new(),
+                delegations: HashMap::new(),
+            };
+            for player in group {
+                PLAYER_GAMES.save(storage, (&Addr::unchecked(player), id), &())?;
+            }
+            games.push(game);
+            round.games.push(id);
+        }
+        tournament.rounds.push(round);
+
+        Ok(tournament_event("round_started", tournament.id)
+            .add_attribute("round", round_number.to_string())
+            .add_attribute("players", players.len().to_string()))
+    }
+
+    // Moves the tournament forward once every game of its current round has
+    // ended, either by starting the next round or by crowning the winner
+    fn advance_tournament(
+        storage: &mut dyn Storage,
+        games: &mut Vec<GameState>,
+        game_id: u64,
+    ) -> StdResult<Vec<Event>> {
+        let Some(tournament_id) = games[game_id as usize].tournament_id else {
+            return Ok(vec![]);
+        };
+        let mut tournament = TOURNAMENTS.load(storage, tournament_id)?;
+        let round = match tournament.rounds.last() {
+            Some(round) => round.clone(),
+            None => return Ok(vec![]),
+        };
+        if !round.games.iter().all(|id| games[*id as usize].ended) {
+            return Ok(vec![]);
+        }
+
+        let mut advancing: Vec<String> = round
+            .games
+            .iter()
+            .map(|id| games[*id as usize].winner.clone())
+            .collect();
+        advancing.extend(round.byes);
+
+        let event = if advancing.len() == 1 {
+            let winner = advancing.remove(0);
+            if let Some(entry_fee) = &tournament.entry_fee {
+                let prize = entry_fee.amount * Uint128::from(tournament.entrants.len() as u64);
+                credit_claimable(storage, &Addr::unchecked(&winner), &entry_fee.denom, prize)?;
+            }
+            tournament.winner = Some(winner.clone());
+            tournament_event("tournament_won", tournament_id).add_attribute("winner", winner)
+        } else {
+            start_round(storage, games, &mut tournament, advancing)?
+        };
+        TOURNAMENTS.save(storage, tournament_id, &tournament)?;
+
+        Ok(vec![event])
+    }
+
+    fn load_tournament(deps: Deps, tournament_id: u64) -> StdResult<Tournament> {
+        TOURNAMENTS
+            .may_load(deps.storage, tournament_id)?
+            .ok_or_else(|| StdError::generic_err("Tournament not found"))
+    }
+
+    // Event emitted for tournament actions, reported as wasm-moviemagic
+    fn tournament_event(action: &str, tournament_id: u64) -> Event {
+        Event::new("moviemagic")
+            .add_attribute("action", action)
+            .add_attribute("tournament_id", tournament_id.to_string())
+    }
+
+    // Season running at the given time, if any
+    fn active_season(storage: &dyn Storage, time: Timestamp) -> StdResult<Option<u64>> {
+        let Some(id) = ACTIVE_SEASON.may_load(storage)?.flatten() else {
+            return Ok(None);
+        };
+        let season = SEASONS.load(storage, id)?;
+        let running = !season.closed && season.start_time <= time && time < season.end_time;
+        Ok(running.then_some(id))
+    }
+
+    // Returns the config if the sender is the owner
+    fn ensure_owner(deps: Deps, info: &MessageInfo) -> StdResult<Config> {
+        let config = CONFIG.load(deps.storage)?;
+        if config.owner.as_ref() != Some(&info.sender) {
+            return Err(StdError::generic_err("Only the owner can do this."));
+        }
+        Ok(config)
+    }
+
+    pub fn set_template_creator(
+        deps: DepsMut,
+        info: MessageInfo,
+        address: String,
+        approved: bool,
+    ) -> StdResult<Response> {
+        ensure_owner(deps.as_ref(), &info)?;
+
+        let address = deps.api.addr_validate(&address)?;
+        if approved {
+            TEMPLATE_CREATORS.save(deps.storage, &address, &true)?;
+        } else {
+            TEMPLATE_CREATORS.remove(deps.storage, &address);
+        }
+
+        Ok(Response::new()
+            .add_attribute("method", "set_template_creator")
+            .add_attribute("address", address)
+            .add_attribute("approved", approved.to_string()))
+    }
+
+    pub fn publish_template(
+        deps: DepsMut,
+        info: MessageInfo,
+        template_id: Option<u64>,
+        title: String,
+        mut template: StoryTemplate,
+    ) -> StdResult<Response> {
+        if template.story.is_empty() {
+            return Err(StdError::generic_err("A template needs a story."));
+        }
+        if template.num_of_adventures == 0 {
+            return Err(StdError::generic_err(
+                "A game needs at least one adventure.",
+            ));
+        }
+        validate_story(&template.story)?;
+        if let Some(buy_in) = &template.buy_in {
+            validate_buy_in(buy_in)?;
+        }
+
+        let is_owner = ensure_owner(deps.as_ref(), &info).is_ok();
+        let is_creator = TEMPLATE_CREATORS
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or(false);
+
+        let info = match template_id {
+            // New version of an existing template
+            Some(template_id) => {
+                let mut template_info = TEMPLATES
+                    .may_load(deps.storage, template_id)?
+                    .ok_or_else(|| StdError::generic_err("Template not found"))?;
+                if !is_owner && info.sender != template_info.creator {
+                    return Err(StdError::generic_err(
+                        "Only the template creator can publish new versions.",
+                    ));
+                }
+                // Revoking a creator also stops new versions of their templates
+                if !is_owner && !is_creator {
+                    return Err(StdError::generic_err(
+                        "Only the owner or approved creators can publish templates.",
+                    ));
+                }
+                template_info.title = title;
+                template_info.latest_version += 1;
+                template_info
+            }
+            None => {
+                if !is_owner && !is_creator {
+                    return Err(StdError::generic_err(
+                        "Only the owner or approved creators can publish templates.",
+                    ));
+                }
+                let id = TEMPLATE_COUNT.may_load(deps.storage)?.unwrap_or_default();
+                TEMPLATE_COUNT.save(deps.storage, &(id + 1))?;
+                StoryTemplateInfo {
+                    id,
+                    title,
+                    creator: info.sender,
+                    latest_version: 1,
+                }
+            }
+        };
+
+        template.template_id = info.id;
+        template.version = info.latest_version;
+        TEMPLATES.save(deps.storage, info.id, &info)?;
+        TEMPLATE_VERSIONS.save(deps.storage, (info.id, info.latest_version), &template)?;
+
+        Ok(Response::new()
+            .add_attribute("method", "publish_template")
+            .add_attribute("template_id", info.id.to_string())
+            .add_attribute("version", info.latest_version.to_string()))
+    }
+
+    pub fn deprecate_template(
+        deps: DepsMut,
+        info: MessageInfo,
+        template_id: u64,
+        version: u32,
+    ) -> StdResult<Response> {
+        let template_info = TEMPLATES
+            .may_load(deps.storage, template_id)?
+            .ok_or_else(|| StdError::generic_err("Template not found"))?;
+        if ensure_owner(deps.as_ref(), &info).is_err() && info.sender != template_info.creator {
+            return Err(StdError::generic_err(
+                "Only the owner or the template creator can deprecate templates.",
+            ));
+        }
+
+        TEMPLATE_VERSIONS.update(deps.storage, (template_id, version), |template| {
+            let mut template =
+                template.ok_or_else(|| StdError::generic_err("Template version not found"))?;
+            template.deprecated = true;
+            Ok::<_, StdError>(template)
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("method", "deprecate_template")
+            .add_attribute("template_id", template_id.to_string())
+            .add_attribute("version", version.to_string()))
+    }
+
+    // Team games require one of their teams to be picked, other games none
+    fn validate_team(teams: &[String], team: Option<String>) -> StdResult<Option<String>> {
+        match team {
+            Some(team) if teams.contains(&team) => Ok(Some(team)),
+            Some(_) if teams.is_empty() => {
+                Err(StdError::generic_err("This game is not played in teams."))
+            }
+            Some(team) => Err(StdError::generic_err(format!(
+                "Team {} is not part of the game.",
+                team
+            ))),
+            None if teams.is_empty() => Ok(None),
+            None => Err(StdError::generic_err(
+                "A team must be chosen to join this game.",
+            )),
+        }
+    }
+
+    fn validate_buy_in(buy_in: &Coin) -> StdResult<()> {
+        if buy_in.amount.is_zero() || buy_in.amount > Uint128::from(u64::MAX) {
+            return Err(StdError::generic_err("Invalid buy-in amount."));
+        }
+        Ok(())
+    }
+
+    // The stake must equal the buy-in and exactly that coin must be sent
+    fn ensure_buy_in_paid(info: &MessageInfo, buy_in: &Coin, game_stake: u64) -> StdResult<()> {
+        if Uint128::from(game_stake) != buy_in.amount || info.funds != [buy_in.clone()] {
+            return Err(StdError::generic_err(format!(
+                "This game requires a buy-in of exactly {}.",
+                buy_in
+            )));
+        }
+        Ok(())
+    }
+
+    // Only buy-in games hold funds, anything sent to other games is refused
+    fn ensure_no_funds(info: &MessageInfo) -> StdResult<()> {
+        if !info.funds.is_empty() {
+            return Err(StdError::generic_err(
+                "This game has no buy-in, so no funds can be sent.",
+            ));
+        }
+        Ok(())
+    }
+
+    // Loads the requested template version, or the latest one, for a new game
+    fn load_template(
+        deps: Deps,
+        template_id: u64,
+        version: Option<u32>,
+    ) -> StdResult<StoryTemplate> {
+        let template_info = TEMPLATES
+            .may_load(deps.storage, template_id)?
+            .ok_or_else(|| StdError::generic_err("Template not found"))?;
+        let version = version.unwrap_or(template_info.latest_version);
+        let template = TEMPLATE_VERSIONS
+            .may_load(deps.storage, (template_id, version))?
+            .ok_or_else(|| StdError::generic_err("Template version not found"))?;
+        if template.deprecated {
+            return Err(StdError::generic_err(
+                "This template version has been deprecated.",
+            ));
+        }
+        Ok(template)
+    }
+
+    // Computes the overall winner of the game, marks it as ended and adds the
+    // results to the stats of each player
+    fn finish_game(storage: &mut dyn Storage, game: &mut GameState) -> StdResult<Event> {
+        let advernture_winners_for_adventure = game.adventure_winners.clone();
+
+        // Compute the overall winner of the game
+
+        // 1. Flatten the adventure winners
+        let winners_across_adventures: Vec<String> = advernture_winners_for_adventure
+            .into_iter()
+            .flatten()
+            .collect();
+
+        // 2. Create map of each player and their winning count. Team games
+        // count the adventures won by each team instead.
+        let mut player_winning_count: BTreeMap<String, u32> = BTreeMap::new();
+        if game.teams.is_empty() {
+            for player in winners_across_adventures {
+                *player_winning_count.entry(player).or_default() += 1;
+            }
+        } else {
+            for winners in &game.adventure_winners {
+                let teams: BTreeSet<&String> = winners
+                    .iter()
+                    .filter_map(|player| game.player_teams.get(player))
+                    .collect();
+                for team in teams {
+                    *player_winning_count.entry(team.clone()).or_default() += 1;
+                }
+            }
+        }
+
+        // 3. Get the player with the highest winning count. Nobody wins if no
+        // adventure had a winner.
+        let winning_player = player_winning_count
+            .into_iter()
+            .max_by_key(|(_, v)| *v)
+            .map(|(k, _)| k)
+            .unwrap_or_default();
+
+        game.winner = winning_player;
+
+        game.winning_reward = (20 * prize_pot(game)) / 100;
+
+        // Mark the game as ended
+        game.ended = true;
+
+        // Games only count towards seasons that are still open
+        let season_id = match game.season_id {
+            Some(season_id) if !SEASONS.load(storage, season_id)?.closed => Some(season_id),
+            _ => None,
+        };
+        let mut paid = 0;
+        for player in &game.players {
+            let (adventures_won, rewards) = player_rewards(game, player);
+            // Winnings of buy-in games are paid from the pot the contract holds
+            if let Some(buy_in) = &game.buy_in {
+                credit_claimable(
+                    storage,
+                    &Addr::unchecked(player),
+                    &buy_in.denom,
+                    Uint128::from(rewards),
+                )?;
+                paid += rewards;
+            }
+            let address = Addr::unchecked(player);
+            let mut scopes = vec![None];
+            scopes.extend(season_id.map(Some));
+            for scope in scopes {
+                let previous = load_stats(storage, scope, &address)?;
+                let mut stats = previous.clone().unwrap_or_default();
+                stats.games_played += 1;
+                if won_game(game, player) {
+                    stats.games_won += 1;
+                }
+                stats.adventures_won += adventures_won;
+                stats.total_staked += game.player_stakes.get(player).copied().unwrap_or_default();
+                stats.total_earned += rewards;
+                save_ranked_stats(storage, scope, &address, previous.as_ref(), &stats)?;
+            }
+        }
+        update_ratings(storage, game)?;
+
+        // Rounding, void adventures and unplayed story adventures leave part
+        // of the pot unpaid. It goes to the owner, or to the host once
+        // ownership has been renounced.
+        if let Some(buy_in) = &game.buy_in {
+            let remainder = prize_pot(game) - paid;
+            let recipient = match CONFIG.load(storage)?.owner {
+                Some(owner) => owner,
+                None => Addr::unchecked(&game.host),
+            };
+            credit_claimable(storage, &recipient, &buy_in.denom, Uint128::from(remainder))?;
+        }
+
+        let mut event =
+            game_event("game_ended", game).add_attribute("reward", game.winning_reward.to_string());
+        if !game.winner.is_empty() {
+            event = event.add_attribute("winner", &game.winner);
+        }
+        Ok(event)
+    }
+
+    // Final standings of an ended game
+    fn end_game_response(game: &GameState) -> EndGameResponse {
+        let mut standings: Vec<PlayerStanding> = game
+            .players
+            .iter()
+            .map(|player| {
+                let (adventures_won, rewards) = player_rewards(game, player);
+                PlayerStanding {
+                    player: player.clone(),
+                    adventures_won,
+                    rewards,
+                }
+            })
+            .collect();
+        standings.sort_by_key(|standing| {
+            (
+                std::cmp::Reverse(standing.adventures_won),
+                !won_game(game, &standing.player),
+            )
+        });
+
+        EndGameResponse {
+            winner: game.winner.clone(),
+            winning_reward: game.winning_reward,
+            standings,
+        }
+    }
+
+    pub fn commit_adventure_answer(
+        deps: DepsMut,
+        info: MessageInfo,
+        name: String,
+        adventure_number: u32,
+        answer_hash: String,
+    ) -> StdResult<Response> {
+        let mut curr_games = STATE.load(deps.storage)?;
+
+        let game = curr_games
+            .games
+            .iter_mut()
+            .find(|game| game.name == name)
+            .ok_or_else(|| StdError::generic_err("Game not found"))?;
+
+        ensure_host_judged(game, &info)?;
+
+        // The commitment is only meaningful if nobody has voted yet
+        if (adventure_number as usize) < game.adventure_votes.len() {
+            return Err(StdError::generic_err(
+                "Voting has already started for this adventure.",
+            ));
+        }
+        match game
+            .adventure_answer_hashes
+            .get_mut(adventure_number as usize)
+        {
+            Some(Some(_)) => {
+                return Err(StdError::generic_err(
+                    "An answer has already been committed for this adventure.",
+                ));
+            }
+            Some(committed_hash) => {
+                *committed_hash = Some(answer_hash.to_lowercase());
+            }
+            None => {
+                return Err(StdError::generic_err("Adventure not found"));
+            }
+        }
+        let event = game_event("answer_committed", game)
+            .add_attribute("adventure", adventure_number.to_string());
+
+        STATE.save(deps.storage, &curr_games)?;
+
+        Ok(Response::new().add_event(event))
+    }
+
+    pub fn reveal_adventure_answer(
+        deps: DepsMut,
+        info: MessageInfo,
+        name: String,
+        adventure_number: u32,
+        option: u32,
+        salt: Option<String>,
+    ) -> StdResult<Response> {
+        let mut curr_games = STATE.load(deps.storage)?;
+
+        let game = curr_games
+            .games
+            .iter_mut()
+            .find(|game| game.name == name)
+            .ok_or_else(|| StdError::generic_err("Game not found"))?;
+
+        ensure_host_judged(game, &info)?;
+
+        // The answer is revealed when voting ends, so revealing it closes the
+        // open adventure
+        if adventure_number < game.current_adventure {
+            return Err(StdError::generic_err(
+                "This adventure has already been closed.",
+            ));
+        }
+        if !game.adventure_open || adventure_number != game.current_adventure {
+            return Err(StdError::generic_err("This adventure is not open."));
+        }
+
+        // Check the revealed answer against the commitment, if there is one
+        if let Some(Some(committed_hash)) =
+            game.adventure_answer_hashes.get(adventure_number as usize)
+        {
+            let salt = salt.ok_or_else(|| {
+                StdError::generic_err("A salt is required to reveal a committed answer.")
+            })?;
+            if hash_answer(option, &salt) != *committed_hash {
+                return Err(StdError::generic_err(
+                    "The answer does not match the committed hash.",
+                ));
+            }
+        }
+
+        ensure_valid_option(game, adventure_number, option)?;
+
+        let answer = game
+            .adventure_answers
+            .get_mut(adventure_number as usize)
+            .ok_or_else(|| StdError::generic_err("Adventure not found"))?;
+        *answer = Some(option);
+        let event = game_event("answer_revealed", game)
+            .add_attribute("adventure", adventure_number.to_string())
+            .add_attribute("option", option.to_string());
+
+        let game_id = game.id;
+        let response = close_adventure(
+            deps.storage,
+            &mut curr_games.games,
+            game_id,
+            Response::new().add_event(event),
+        )?;
+
+        STATE.save(deps.storage, &curr_games)?;
+
+        Ok(response)
+    }
+
+    fn ensure_host_judged(game: &GameState, info: &MessageInfo) -> StdResult<()> {
+        if info.sender != game.host {
+            return Err(StdError::generic_err(
+                "Only the host can submit adventure answers.",
+            ));
+        }
+        if game.scoring_rule != ScoringRule::HostJudged {
+            return Err(StdError::generic_err("This game is not host-judged."));
+        }
+        Ok(())
+    }
+
+    // Event emitted for actions on a game, reported as wasm-moviemagic
+    fn game_event(action: &str, game: &GameState) -> Event {
+        Event::new("moviemagic")
+            .add_attribute("action", action)
+            .add_attribute("game", &game.name)
+            .add_attribute("game_id", game.id.to_string())
+    }
+
+    fn validate_story(story: &[StoryNode]) -> StdResult<()> {
+        for node in story {
+            if node.next.len() < 2 {
+                return Err(StdError::generic_err(
+                    "Every story node needs at least two options.",
+                ));
+            }
+            if let Some(content) = &node.content {
+                if content.option_labels.len() != node.next.len() {
+                    return Err(StdError::generic_err(
+                        "Story node option labels must match its next nodes.",
+                    ));
+                }
+            }
+            if node
+                .next
+                .iter()
+                .flatten()
+                .any(|next| *next as usize >= story.len())
+            {
+                return Err(StdError::generic_err(
+                    "Story node points to a node that does not exist.",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Hex encoded sha256 of "<option>:<salt>", as committed by the host
+    fn hash_answer(option: u32, salt: &str) -> String {
+        hex::encode(Sha256::digest(format!("{}:{}", option, salt).as_bytes()))
+    }
+}
+
+// Group the votes by option and pick the winning option according to the
+// scoring rule. Ties go to the higher option number. Host-judged games
+// use the answer submitted by the host instead. Returns the players who
+// voted for the winning option along with the number of those votes, or
+// None if there is no winning option yet.
+fn tally_adventure(
+    adventure_votes: &HashMap<String, u32>,
+    scoring_rule: ScoringRule,
+    answer: Option<u32>,
+) -> Option<(u32, Vec<String>, u32)> {
+    // Generate map to track who votes for given options
+    let mut votes_count_map: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for (player, vote) in adventure_votes.iter() {
+        votes_count_map
+            .entry(*vote)
+            .or_default()
+            .push(player.clone());
+    }
+
+    if scoring_rule == ScoringRule::HostJudged {
+        let answer = answer?;
+        let mut winning_players = votes_count_map.remove(&answer).unwrap_or_default();
+        winning_players.sort();
+        let winning_vote_count = winning_players.len() as u32;
+        return Some((answer, winning_players, winning_vote_count));
+    }
+
+    // Options are iterated from the highest number down so that the first
+    // option found with the best count wins a tie
+    let mut options = votes_count_map.into_iter().rev();
+    let first = options.next()?;
+    let (winning_option, mut winning_players) = options.fold(first, |best, candidate| {
+        let better = match scoring_rule {
+            ScoringRule::Minority => candidate.1.len() < best.1.len(),
+            _ => candidate.1.len() > best.1.len(),
+        };
+        if better {
+            candidate
+        } else {
+            best
+        }
+    });
+
+    winning_players.sort();
+    let winning_vote_count = winning_players.len() as u32;
+    Some((winning_option, winning_players, winning_vote_count))
+}
+
+// Story node played by the given adventure, if the game follows a story
+fn story_node(game: &GameState, adventure_number: u32) -> Option<&StoryNode> {
+    let node_id = game.story_path.get(adventure_number as usize)?;
+    game.story.get(*node_id as usize)
+}
+
+// Options are 1-based, as 0 stands for a void adventure. When the
+// adventure has content or is part of a story, the option must also
+// match one of its labels or branches.
+fn ensure_valid_option(game: &GameState, adventure_number: u32, option: u32) -> StdResult<()> {
+    if option == 0 {
+        return Err(StdError::generic_err(
+            "This option does not exist for the adventure.",
+        ));
+    }
+    let num_of_options = match story_node(game, adventure_number) {
+        Some(node) => Some(node.next.len()),
+        None => game
+            .adventure_contents
+            .get(adventure_number as usize)
+            .cloned()
+            .flatten()
+            .map(|content| content.option_labels.len()),
+    };
+    if let Some(num_of_options) = num_of_options {
+        if option as usize > num_of_options {
+            return Err(StdError::generic_err(
+                "This option does not exist for the adventure.",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// A player who voted or abstained can only choose again in games that allow
+// vote changes
+fn ensure_can_choose(game: &GameState, adventure_number: u32, player: &String) -> StdResult<()> {
+    let index = adventure_number as usize;
+    let voted = game
+        .adventure_votes
+        .get(index)
+        .is_some_and(|votes| votes.contains_key(player));
+    let abstained = game
+        .adventure_abstentions
+        .get(index)
+        .is_some_and(|abstentions| abstentions.contains(player));
+    if (voted || abstained) && !game.allow_vote_change {
+        return Err(StdError::generic_err(
+            "This player has already voted for this adventure.",
+        ));
+    }
+    Ok(())
+}
+
+// Result of closing an adventure with the given votes
+pub(crate) struct AdventureOutcome {
+    pub winning_option: u32,
+    pub winners: Vec<String>,
+    pub winning_vote_count: u32,
+    pub reward: u64, // Reward paid to each winner
+}
+
+// Computes the outcome of an adventure for the given votes without touching
+// the stored game. Used when closing adventures and for the projections
+// returned by queries. Returns None while there is no winning option.
+pub(crate) fn adventure_outcome(
+    game: &GameState,
+    adventure_number: u32,
+    votes: &HashMap<String, u32>,
+) -> Option<AdventureOutcome> {
+    let answer = game
+        .adventure_answers
+        .get(adventure_number as usize)
+        .copied()
+        .flatten();
+    let votes = &counted_votes(game, adventure_number, votes);
+    let (winning_option, winners, winning_vote_count) = if game.teams.is_empty() {
+        tally_adventure(votes, game.scoring_rule, answer)?
+    } else {
+        // The majority choice of each team is its vote, and the members of
+        // the winning teams who voted share the reward
+        let team_votes: HashMap<String, u32> = game
+            .teams
+            .iter()
+            .filter_map(|team| {
+                let member_votes: HashMap<String, u32> = votes
+                    .iter()
+                    .filter(|(player, _)| game.player_teams.get(*player) == Some(team))
+                    .map(|(player, vote)| (player.clone(), *vote))
+                    .collect();
+                let (team_vote, _, _) =
+                    tally_adventure(&member_votes, ScoringRule::Majority, None)?;
+                Some((team.clone(), team_vote))
+            })
+            .collect();
+        let (winning_option, winning_teams, _) =
+            tally_adventure(&team_votes, game.scoring_rule, answer)?;
+        let mut winners: Vec<String> = votes
+            .keys()
+            .filter(|player| {
+                game.player_teams
+                    .get(*player)
+                    .is_some_and(|team| winning_teams.contains(team))
+            })
+            .cloned()
+            .collect();
+        winners.sort();
+        let winning_vote_count = winners.len() as u32;
+        (winning_option, winners, winning_vote_count)
+    };
+
+    // Calculate the reward amount for each player
+    let reward = if winning_vote_count == 0 {
+        0
+    } else {
+        (game.adventure_funds / (game.num_of_adventures as u64)) / (winning_vote_count as u64)
+    };
+
+    Some(AdventureOutcome {
+        winning_option,
+        winners,
+        winning_vote_count,
+        reward,
+    })
+}
+
+// Votes of the adventure with the delegated votes added. A delegator who
+// neither voted nor abstained votes like their delegate.
+fn counted_votes(
+    game: &GameState,
+    adventure_number: u32,
+    votes: &HashMap<String, u32>,
+) -> HashMap<String, u32> {
+    let abstentions = game.adventure_abstentions.get(adventure_number as usize);
+    let mut counted = votes.clone();
+    for (delegator, delegate) in &game.delegations {
+        if votes.contains_key(delegator)
+            || abstentions.is_some_and(|abstentions| abstentions.contains(delegator))
+        {
+            continue;
+        }
+        if let Some(vote) = votes.get(delegate) {
+            counted.insert(delegator.clone(), *vote);
+        }
+    }
+    counted
+}
+
+// Votes received by each option, in option order
+fn option_tally(
+    game: &GameState,
+    adventure_number: u32,
+    votes: &HashMap<String, u32>,
+) -> Vec<OptionTally> {
+    let mut tally: BTreeMap<u32, (u32, u32)> = BTreeMap::new();
+    for option in votes.values() {
+        tally.entry(*option).or_default().0 += 1;
+    }
+    for option in counted_votes(game, adventure_number, votes).values() {
+        tally.entry(*option).or_default().1 += 1;
+    }
+    tally
+        .into_iter()
+        .map(|(option, (votes, weight))| OptionTally {
+            option,
+            votes,
+            weight,
+        })
+        .collect()
+}
+
+// Number of adventures won by the player and the rewards earned in the game,
+// including the winning reward
+fn player_rewards(game: &GameState, player: &String) -> (u32, u64) {
+    let mut adventures_won = 0;
+    let mut rewards = 0;
+    for (winners, reward) in game.adventure_winners.iter().zip(&game.adventure_rewards) {
+        if winners.contains(player) {
+            adventures_won += 1;
+            rewards += reward;
+        }
+    }
+    if won_game(game, player) {
+        // Members of the winning team share the winning reward
+        let winners = if game.teams.is_empty() {
+            1
+        } else {
+            game.players
+                .iter()
+                .filter(|member| won_game(game, member))
+                .count() as u64
+        };
+        rewards += game.winning_reward / winners;
+    }
+    (adventures_won, rewards)
+}
+
+// Whether the player won the game, directly or as a member of the winning
+// team
+fn won_game(game: &GameState, player: &String) -> bool {
+    if game.teams.is_empty() {
+        *player == game.winner
+    } else {
+        !game.winner.is_empty() && game.player_teams.get(player) == Some(&game.winner)
+    }
+}
+
+// Name of the leaderboard ranking by the criteria, across all games or
+// within a season
+fn ranking_board(sort_by: LeaderboardSort, season_id: Option<u64>) -> String {
+    let criteria = match sort_by {
+        LeaderboardSort::Wins => "wins",
+        LeaderboardSort::Earnings => "earnings",
+    };
+    match season_id {
+        Some(season_id) => format!("{}:{}", criteria, season_id),
+        None => criteria.to_string(),
+    }
+}
+
+// Place of the stats in a ranking. Lower keys rank higher, and ties are
+// listed in address order.
+fn rank_key(stats: &PlayerStats, sort_by: LeaderboardSort) -> u64 {
+    let score = match sort_by {
+        LeaderboardSort::Wins => ((stats.games_won as u64) << 32) | stats.adventures_won as u64,
+        LeaderboardSort::Earnings => stats.total_earned,
+    };
+    u64::MAX - score
+}
+
+// Stats of the address across all games, or within the season
+fn load_stats(
+    storage: &dyn Storage,
+    season_id: Option<u64>,
+    address: &Addr,
+) -> StdResult<Option<PlayerStats>> {
+    match season_id {
+        Some(season_id) => SEASON_STATS.may_load(storage, (season_id, address)),
+        None => PLAYER_STATS.may_load(storage, address),
+    }
+}
+
+// Saves the stats of the address and moves it to its new place in every
+// ranking
+fn save_ranked_stats(
+    storage: &mut dyn Storage,
+    season_id: Option<u64>,
+    address: &Addr,
+    previous: Option<&PlayerStats>,
+    stats: &PlayerStats,
+) -> StdResult<()> {
+    for sort_by in [LeaderboardSort::Wins, LeaderboardSort::Earnings] {
+        let board = ranking_board(sort_by, season_id);
+        if let Some(previous) = previous {
+            RANKINGS.remove(storage, (&board, rank_key(previous, sort_by), address));
+        }
+        RANKINGS.save(storage, (&board, rank_key(stats, sort_by), address), &())?;
+    }
+    match season_id {
+        Some(season_id) => SEASON_STATS.save(storage, (season_id, address), stats),
+        None => PLAYER_STATS.save(storage, address, stats),
+    }
+}
+
+// Rating of the address, or the initial rating if it has none yet
+fn player_rating(storage: &dyn Storage, address: &Addr) -> StdResult<PlayerRating> {
+    Ok(RATINGS.may_load(storage, address)?.unwrap_or(PlayerRating {
+        rating: INITIAL_RATING,
+        games_rated: 0,
+    }))
+}
+
+// Expected score, in thousandths, of a player against an opponent
+fn expected_score(rating: u32, opponent_rating: u32) -> i64 {
+    let difference = rating as i64 - opponent_rating as i64;
+    let index = ((difference.abs() + 12) / 25).min(EXPECTED_SCORE.len() as i64 - 1) as usize;
+    if difference >= 0 {
+        EXPECTED_SCORE[index]
+    } else {
+        1000 - EXPECTED_SCORE[index]
+    }
+}
+
+// Updates the rating of every player of an ended game. The game counts as a
+// match between each pair of players, won by the one who won more
+// adventures, and the rating changes are averaged over the opponents.
+fn update_ratings(storage: &mut dyn Storage, game: &GameState) -> StdResult<()> {
+    let players = game
+        .players
+        .iter()
+        .map(|player| {
+            let address = Addr::unchecked(player);
+            let rating = player_rating(storage, &address)?;
+            Ok((address, rating, player_rewards(game, player).0))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    if players.len() < 2 {
+        return Ok(());
+    }
+    let opponents = players.len() as i64 - 1;
+
+    for (address, rating, adventures_won) in &players {
+        let mut score_difference = 0;
+        for (opponent, opponent_rating, opponent_adventures_won) in &players {
+            if opponent == address {
+                continue;
+            }
+            let score = match adventures_won.cmp(opponent_adventures_won) {
+                Ordering::Greater => 1000,
+                Ordering::Equal => 500,
+                Ordering::Less => 0,
+            };
+            score_difference += score - expected_score(rating.rating, opponent_rating.rating);
+        }
+        let change = RATING_K * score_difference / (1000 * opponents);
+        RATINGS.save(
+            storage,
+            address,
+            &PlayerRating {
+                rating: (rating.rating as i64 + change).max(0) as u32,
+                games_rated: rating.games_rated + 1,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+// Average rating of the players who joined the game
+fn average_rating(storage: &dyn Storage, game: &GameState) -> StdResult<u32> {
+    let mut total = 0u64;
+    for player in &game.players {
+        total += player_rating(storage, &Addr::unchecked(player))?.rating as u64;
+    }
+    Ok((total / game.players.len().max(1) as u64) as u32)
+}
+
+mod query {
+    use cosmwasm_std::{Coin, StdError};
+
+    use cw_storage_plus::Bound;
+
+    use crate::msg::{
+        AdventureResponse, AdventureTallyResponse, BracketGame, BracketRound, ClaimableResponse,
+        GamePhase, GamePlayerVote, GameSummary, LeaderboardEntry, LeaderboardResponse,
+        ListGamesResponse, ListSeasonsResponse, ListTemplatesResponse, ListTournamentsResponse,
+        PlayerGameSummary, PlayerGamesResponse, SimulatePayoutResponse, StoryPathResponse,
+        StoryStep, TemplateResponse, TournamentResponse,
+    };
+    use crate::state::{
+        PrivateGameAccess, Season, CLAIMABLE, PLAYER_GAMES, SEASONS, TEMPLATES, TEMPLATE_VERSIONS,
+        TOURNAMENTS,
+    };
+
+    use super::*;
+
+    const DEFAULT_LIMIT: u32 = 10;
+    const MAX_LIMIT: u32 = 30;
+
+    pub fn game(deps: Deps, name: String) -> StdResult<GameState> {
+        let curr_games = STATE.load(deps.storage)?;
+
+        curr_games
+            .games
+            .into_iter()
+            .find(|game| game.name == name)
+            .ok_or_else(|| StdError::generic_err("Game not found"))
+    }
+
+    // Hides who is invited to a private game and its join code hash, keeping
+    // only the fact that the game is private
+    pub fn public_game(mut game: GameState) -> GameState {
+        if game.private_access.is_some() {
+            game.private_access = Some(PrivateGameAccess {
+                invite_list: vec![],
+                join_code_hash: None,
+            });
+        }
+        game
+    }
+
+    pub fn adventure(
+        deps: Deps,
+        name: String,
+        adventure_number: u32,
+    ) -> StdResult<AdventureResponse> {
+        let mut game = game(deps, name)?;
+
+        let index = adventure_number as usize;
+        if index >= game.adventure_votes.len() {
+            return Err(StdError::generic_err("Adventure has not been opened yet."));
+        }
+
+        let mut votes: Vec<GamePlayerVote> = game.adventure_votes[index]
+            .iter()
+            .map(|(player, vote)| GamePlayerVote {
+                player: player.clone(),
+                vote: *vote,
+            })
+            .collect();
+        votes.sort_by(|a, b| a.player.cmp(&b.player));
+
+        Ok(AdventureResponse {
+            adventure_number,
+            content: game.adventure_contents.get(index).cloned().flatten(),
+            open: game.adventure_open && adventure_number == game.current_adventure,
+            votes,
+            abstentions: game
+                .adventure_abstentions
+                .get(index)
+                .cloned()
+                .unwrap_or_default(),
+            winners: game.adventure_winners.get_mut(index).map(std::mem::take),
+            reward: game.adventure_rewards.get(index).copied(),
+        })
+    }
+
+    pub fn adventure_tally(
+        deps: Deps,
+        name: String,
+        adventure_number: u32,
+    ) -> StdResult<AdventureTallyResponse> {
+        let game = game(deps, name)?;
+
+        let index = adventure_number as usize;
+        let votes = game
+            .adventure_votes
+            .get(index)
+            .ok_or_else(|| StdError::generic_err("Adventure has not been opened yet."))?;
+        let abstentions = game
+            .adventure_abstentions
+            .get(index)
+            .map_or(0, |abstentions| abstentions.len());
+        let open = game.adventure_open && adventure_number == game.current_adventure;
+        let yet_to_vote = if open {
+            let counted = counted_votes(&game, adventure_number, votes).len();
+            game.players.len().saturating_sub(counted + abstentions)
+        } else {
+            0
+        };
+        let outcome = adventure_outcome(&game, adventure_number, votes);
+
+        Ok(AdventureTallyResponse {
+            adventure_number,
+            open,
+            tally: option_tally(&game, adventure_number, votes),
+            abstentions: abstentions as u32,
+            yet_to_vote: yet_to_vote as u32,
+            projected_winning_option: outcome.as_ref().map(|outcome| outcome.winning_option),
+            projected_winners: outcome
+                .as_ref()
+                .map_or(0, |outcome| outcome.winning_vote_count),
+            projected_reward: outcome.map(|outcome| outcome.reward),
+        })
+    }
+
+    pub fn simulate_payout(
+        deps: Deps,
+        name: String,
+        adventure_number: u32,
+        player: String,
+        option: u32,
+    ) -> StdResult<SimulatePayoutResponse> {
+        let game = game(deps, name)?;
+
+        if !game.adventure_open || adventure_number != game.current_adventure {
+            return Err(StdError::generic_err("This adventure is not open."));
+        }
+        let player = deps.api.addr_validate(&player)?.to_string();
+        if !game.players.contains(&player) {
+            return Err(StdError::generic_err(
+                "This player is not part of the game.",
+            ));
+        }
+
+        // Apply the hypothetical vote the same way a real vote would be
+        ensure_valid_option(&game, adventure_number, option)?;
+        ensure_can_choose(&game, adventure_number, &player)?;
+        let mut votes = game.adventure_votes[adventure_number as usize].clone();
+        votes.insert(player.clone(), option);
+        let outcome = adventure_outcome(&game, adventure_number, &votes);
+
+        Ok(SimulatePayoutResponse {
+            winning_option: outcome.as_ref().map(|outcome| outcome.winning_option),
+            winners: outcome
+                .as_ref()
+                .map_or(0, |outcome| outcome.winning_vote_count),
+            reward: outcome.as_ref().map(|outcome| outcome.reward),
+            payout: outcome
+                .filter(|outcome| outcome.winners.contains(&player))
+                .map_or(0, |outcome| outcome.reward),
+        })
+    }
+
+    pub fn story_path(deps: Deps, name: String) -> StdResult<StoryPathResponse> {
+        let game = game(deps, name)?;
+
+        let steps = game
+            .story_path
+            .iter()
+            .enumerate()
+            .map(|(adventure_number, node)| StoryStep {
+                adventure_number: adventure_number as u32,
+                node: *node,
+                winning_option: game
+                    .adventure_winning_options
+                    .get(adventure_number)
+                    .copied(),
+            })
+            .collect();
+
+        Ok(StoryPathResponse {
+            steps,
+            finished: game.ended,
+        })
+    }
+
+    pub fn list_games(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        include_private: bool,
+        min_rating: Option<u32>,
+        max_rating: Option<u32>,
+    ) -> StdResult<ListGamesResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(|id| id as usize + 1).unwrap_or_default();
+        let curr_games = STATE.load(deps.storage)?;
+
+        let mut games = vec![];
+        for game in curr_games.games.iter().skip(start) {
+            if !include_private && game.private_access.is_some() {
+                continue;
+            }
+            if min_rating.is_some() || max_rating.is_some() {
+                let rating = average_rating(deps.storage, game)?;
+                if min_rating.is_some_and(|min| rating < min)
+                    || max_rating.is_some_and(|max| rating > max)
+                {
+                    continue;
+                }
+            }
+            games.push(game_summary(game));
+            if games.len() == limit {
+                break;
+            }
+        }
+
+        Ok(ListGamesResponse { games })
+    }
+
+    pub fn player_games(
+        deps: Deps,
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        phase: Option<GamePhase>,
+    ) -> StdResult<PlayerGamesResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+        let address = deps.api.addr_validate(&address)?;
+        let curr_games = STATE.load(deps.storage)?;
+
+        let mut games = vec![];
+        for id in PLAYER_GAMES
+            .prefix(&address)
+            .keys(deps.storage, start, None, Order::Ascending)
+        {
+            let game = curr_games
+                .games
+                .get(id? as usize)
+                .ok_or_else(|| StdError::generic_err("Game not found"))?;
+            let game_phase = if game.ended {
+                GamePhase::Ended
+            } else if game.started {
+                GamePhase::InProgress
+            } else {
+                GamePhase::Open
+            };
+            if phase.as_ref().is_some_and(|phase| *phase != game_phase) {
+                continue;
+            }
+
+            let player = address.to_string();
+            games.push(PlayerGameSummary {
+                game: game_summary(game),
+                stake: game.player_stakes.get(&player).copied().unwrap_or_default(),
+                votes: game
+                    .adventure_votes
+                    .iter()
+                    .map(|votes| votes.get(&player).copied())
+                    .collect(),
+                winnings: player_rewards(game, &player).1,
+            });
+            if games.len() == limit {
+                break;
+            }
+        }
+
+        Ok(PlayerGamesResponse { games })
+    }
+
+    pub fn player_stats(deps: Deps, address: String) -> StdResult<LeaderboardEntry> {
+        let address = deps.api.addr_validate(&address)?;
+        let stats = PLAYER_STATS
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default();
+
+        Ok(leaderboard_entry(address.to_string(), stats))
+    }
+
+    // Reads a page of the rankings index, which keeps the players of each
+    // board ordered by their rank key so only the page is loaded
+    pub fn leaderboard(
+        deps: Deps,
+        sort_by: LeaderboardSort,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        season_id: Option<u64>,
+    ) -> StdResult<LeaderboardResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        if let Some(season_id) = season_id {
+            season(deps, season_id)?;
+        }
+
+        // Pages continue after the place of the given address
+        let start = match start_after {
+            Some(address) => {
+                let address = deps.api.addr_validate(&address)?;
+                match load_stats(deps.storage, season_id, &address)? {
+                    Some(stats) => Some((rank_key(&stats, sort_by), address)),
+                    None => return Ok(LeaderboardResponse { entries: vec![] }),
+                }
+            }
+            None => None,
+        };
+        let board = ranking_board(sort_by, season_id);
+        let entries = RANKINGS
+            .sub_prefix(&board)
+            .keys(
+                deps.storage,
+                start
+                    .as_ref()
+                    .map(|(key, address)| Bound::exclusive((*key, address))),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .map(|item| {
+                let (_, address) = item?;
+                let stats = load_stats(deps.storage, season_id, &address)?.unwrap_or_default();
+                Ok(leaderboard_entry(address.to_string(), stats))
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(LeaderboardResponse { entries })
+    }
+
+    pub fn season(deps: Deps, season_id: u64) -> StdResult<Season> {
+        SEASONS
+            .may_load(deps.storage, season_id)?
+            .ok_or_else(|| StdError::generic_err("Season not found"))
+    }
+
+    pub fn tournament(deps: Deps, tournament_id: u64) -> StdResult<TournamentResponse> {
+        let tournament = TOURNAMENTS
+            .may_load(deps.storage, tournament_id)?
+            .ok_or_else(|| StdError::generic_err("Tournament not found"))?;
+        let curr_games = STATE.load(deps.storage)?;
+
+        let bracket = tournament
+            .rounds
+            .iter()
+            .map(|round| BracketRound {
+                games: round
+                    .games
+                    .iter()
+                    .map(|id| {
+                        let game = &curr_games.games[*id as usize];
+                        BracketGame {
+                            game_id: *id,
+                            name: game.name.clone(),
+                            players: game.players.clone(),
+                            ended: game.ended,
+                            winner: game.ended.then(|| game.winner.clone()),
+                        }
+                    })
+                    .collect(),
+                byes: round.byes.clone(),
+            })
+            .collect();
+
+        Ok(TournamentResponse {
+            tournament,
+            bracket,
+        })
+    }
+
+    pub fn list_tournaments(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ListTournamentsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let tournaments = TOURNAMENTS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, tournament)| tournament))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListTournamentsResponse { tournaments })
+    }
+
+    pub fn claimable(deps: Deps, address: String) -> StdResult<ClaimableResponse> {
+        let address = deps.api.addr_validate(&address)?;
+        let balances = CLAIMABLE
+            .prefix(&address)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ClaimableResponse { balances })
+    }
+
+    pub fn list_seasons(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ListSeasonsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let seasons = SEASONS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, season)| season))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListSeasonsResponse { seasons })
+    }
+
+    fn leaderboard_entry(address: String, stats: PlayerStats) -> LeaderboardEntry {
+        LeaderboardEntry {
+            address,
+            net_pnl: stats.total_earned as i64 - stats.total_staked as i64,
+            stats,
+        }
+    }
+
+    fn game_summary(game: &GameState) -> GameSummary {
+        GameSummary {
+            id: game.id,
+            name: game.name.clone(),
+            host: game.host.clone(),
+            num_of_players: game.players.len() as u32,
+            max_players: game.max_players,
+            buy_in: game.buy_in.clone(),
+            started: game.started,
+            ended: game.ended,
+            private: game.private_access.is_some(),
+        }
+    }
+
+    pub fn template(
+        deps: Deps,
+        template_id: u64,
+        version: Option<u32>,
+    ) -> StdResult<TemplateResponse> {
+        let info = TEMPLATES
+            .may_load(deps.storage, template_id)?
+            .ok_or_else(|| StdError::generic_err("Template not found"))?;
+        let template = TEMPLATE_VERSIONS
+            .may_load(
+                deps.storage,
+                (template_id, version.unwrap_or(info.latest_version)),
+            )?
+            .ok_or_else(|| StdError::generic_err("Template version not found"))?;
+
+        Ok(TemplateResponse { info, template })
+    }
+
+    pub fn list_templates(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<ListTemplatesResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let templates = TEMPLATES
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, info)| info))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ListTemplatesResponse { templates })
     }
 }
@@ -3,20 +3,22 @@ mod tests {
     use crate::helpers::CwTemplateContract;
     use crate::msg::InstantiateMsg;
     use cosmwasm_std::{Addr, Coin, Empty, Uint128};
-    use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+    use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
 
     pub fn contract_template() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
             crate::contract::execute,
             crate::contract::instantiate,
             crate::contract::query,
-        );
+        )
+        .with_migrate(crate::contract::migrate);
         Box::new(contract)
     }
 
     const USER: &str = "USER";
     const ADMIN: &str = "ADMIN";
     const NATIVE_DENOM: &str = "denom";
+    const PLAYER_ACCOUNTS: [&str; 3] = ["alice", "bob", "carol"];
 
     fn mock_app() -> App {
         AppBuilder::new().build(|router, _, storage| {
@@ -31,6 +33,19 @@ mod tests {
                     }],
                 )
                 .unwrap();
+            for player in PLAYER_ACCOUNTS {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &Addr::unchecked(player),
+                        vec![Coin {
+                            denom: NATIVE_DENOM.to_string(),
+                            amount: Uint128::new(1000),
+                        }],
+                    )
+                    .unwrap();
+            }
         })
     }
 
@@ -55,17 +70,2526 @@ mod tests {
         (app, cw_template_contract)
     }
 
-    mod count {
+    mod game {
         use super::*;
-        use crate::msg::ExecuteMsg;
+        use crate::contract::{INITIAL_RATING, MAX_PLAYERS_PER_GAME};
+        use crate::msg::{
+            AdventureResponse, AdventureStopResponse, AdventureTallyResponse, ClaimableResponse,
+            EndGameResponse, ExecuteMsg, GamePhase, InitGameResponse, LeaderboardEntry,
+            LeaderboardResponse, LeaderboardSort, ListGamesResponse, ListSeasonsResponse,
+            MigrateMsg, OptionTally, PausedResponse, PlayerGamesResponse, QueryMsg,
+            SimulatePayoutResponse, StoryPathResponse, TemplateResponse, TournamentResponse,
+        };
+        use crate::state::{
+            AdventureContent, Config, GameState, PlayerRating, PrivateGameAccess, ScoringRule,
+            Season, StoryNode,
+        };
+        use crate::ContractError;
+        use cosmwasm_std::{coin, from_binary, StdResult, Storage, Timestamp};
+        use cosmwasm_storage::PrefixedStorage;
+
+        const PLAYERS: [&str; 3] = PLAYER_ACCOUNTS;
+
+        fn execute(app: &mut App, contract: &CwTemplateContract, msg: ExecuteMsg) {
+            execute_as(app, contract, USER, msg).unwrap();
+        }
+
+        fn execute_as(
+            app: &mut App,
+            contract: &CwTemplateContract,
+            sender: &str,
+            msg: ExecuteMsg,
+        ) -> Result<AppResponse, String> {
+            app.execute_contract(Addr::unchecked(sender), contract.addr(), &msg, &[])
+                .map_err(|err| err.root_cause().to_string())
+        }
+
+        fn load_game(app: &App, contract: &CwTemplateContract, name: &str) -> GameState {
+            app.wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::Game {
+                        name: name.to_string(),
+                    },
+                )
+                .unwrap()
+        }
+
+        fn init_game_msg() -> ExecuteMsg {
+            ExecuteMsg::InitGame {
+                name: "movie".to_string(),
+                player: PLAYERS[0].to_string(),
+                game_stake: 100,
+                num_of_adventures: Some(1),
+                scoring_rule: None,
+                allow_vote_change: None,
+                story: None,
+                template_id: None,
+                template_version: None,
+                buy_in: None,
+                min_players: None,
+                max_players: None,
+                private_access: None,
+                teams: None,
+                team: None,
+            }
+        }
+
+        // Creates the game hosted by alice, lets bob and carol join and starts it
+        fn setup_game(init_msg: ExecuteMsg) -> (App, CwTemplateContract) {
+            let (app, contract) = proper_instantiate();
+            setup_game_with(app, contract, init_msg)
+        }
+
+        fn setup_game_with(
+            mut app: App,
+            contract: CwTemplateContract,
+            init_msg: ExecuteMsg,
+        ) -> (App, CwTemplateContract) {
+            execute(&mut app, &contract, init_msg);
+            for player in &PLAYERS[1..] {
+                execute(
+                    &mut app,
+                    &contract,
+                    ExecuteMsg::AddGamePlayer {
+                        name: "movie".to_string(),
+                        player: player.to_string(),
+                        game_stake: 100,
+                        join_code: None,
+                        team: None,
+                    },
+                );
+            }
+            execute(
+                &mut app,
+                &contract,
+                ExecuteMsg::StartGame {
+                    name: "movie".to_string(),
+                },
+            );
+            (app, contract)
+        }
+
+        fn open_adventure(app: &mut App, contract: &CwTemplateContract, adventure_number: u32) {
+            execute_as(
+                app,
+                contract,
+                PLAYERS[0],
+                ExecuteMsg::OpenAdventure {
+                    name: "movie".to_string(),
+                    adventure_number,
+                    content: None,
+                },
+            )
+            .unwrap();
+        }
+
+        fn vote(player: &str, vote: u32) -> ExecuteMsg {
+            ExecuteMsg::VoteForAdventure {
+                name: "movie".to_string(),
+                player: player.to_string(),
+                adventure_number: 0,
+                vote,
+            }
+        }
+
+        // Starts a three player game with one adventure where alice and bob
+        // vote for option 1 and carol votes for option 2
+        fn play_first_adventure(scoring_rule: Option<ScoringRule>) -> (App, CwTemplateContract) {
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                scoring_rule: rule, ..
+            } = &mut init_msg
+            {
+                *rule = scoring_rule;
+            }
+            let (mut app, contract) = setup_game(init_msg);
+            if scoring_rule == Some(ScoringRule::HostJudged) {
+                // The host commits to option 2 before anyone votes
+                execute_as(
+                    &mut app,
+                    &contract,
+                    PLAYERS[0],
+                    ExecuteMsg::CommitAdventureAnswer {
+                        name: "movie".to_string(),
+                        adventure_number: 0,
+                        answer_hash: sha256_hex("2:popcorn"),
+                    },
+                )
+                .unwrap();
+            }
+            open_adventure(&mut app, &contract, 0);
+            for (player, option) in PLAYERS.iter().zip([1, 1, 2]) {
+                execute_as(&mut app, &contract, player, vote(player, option)).unwrap();
+            }
+            (app, contract)
+        }
+
+        fn stop_first_adventure(
+            app: &mut App,
+            contract: &CwTemplateContract,
+        ) -> Result<AppResponse, String> {
+            execute_as(
+                app,
+                contract,
+                PLAYERS[0],
+                ExecuteMsg::GameAdventureStop {
+                    name: "movie".to_string(),
+                    adventure_number: 0,
+                },
+            )
+        }
+
+        // Value of an attribute of the moviemagic event with the given action
+        fn event_attr(res: &AppResponse, action: &str, key: &str) -> Option<String> {
+            let event = res.events.iter().find(|event| {
+                event.ty == "wasm-moviemagic"
+                    && event
+                        .attributes
+                        .iter()
+                        .any(|attr| attr.key == "action" && attr.value == action)
+            })?;
+            event
+                .attributes
+                .iter()
+                .find(|attr| attr.key == key)
+                .map(|attr| attr.value.clone())
+        }
+
+        fn sha256_hex(preimage: &str) -> String {
+            use sha2::{Digest, Sha256};
+            hex::encode(Sha256::digest(preimage.as_bytes()))
+        }
+
+        #[test]
+        fn majority_wins_by_default() {
+            let (mut app, contract) = play_first_adventure(None);
+            stop_first_adventure(&mut app, &contract).unwrap();
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.scoring_rule, ScoringRule::Majority);
+            assert_eq!(
+                game.adventure_winners,
+                vec![vec!["alice".to_string(), "bob".to_string()]]
+            );
+            // 80% of the 300 pot split between two winners
+            assert_eq!(game.adventure_rewards, vec![120]);
+        }
+
+        #[test]
+        fn minority_wins_when_selected() {
+            let (mut app, contract) = play_first_adventure(Some(ScoringRule::Minority));
+            stop_first_adventure(&mut app, &contract).unwrap();
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.scoring_rule, ScoringRule::Minority);
+            assert_eq!(game.adventure_winners, vec![vec!["carol".to_string()]]);
+            assert_eq!(game.adventure_winning_votes, vec![1]);
+            assert_eq!(game.adventure_rewards, vec![240]);
+        }
+
+        #[test]
+        fn host_judged_rewards_revealed_answer() {
+            let (mut app, contract) = play_first_adventure(Some(ScoringRule::HostJudged));
+
+            // Revealing the answer is the only way to close the adventure
+            let err = stop_first_adventure(&mut app, &contract).unwrap_err();
+            assert!(err.contains("closed by revealing the answer"));
+
+            let reveal = |salt: &str| ExecuteMsg::RevealAdventureAnswer {
+                name: "movie".to_string(),
+                adventure_number: 0,
+                option: 2,
+                salt: Some(salt.to_string()),
+            };
+            // Only the host may reveal, and only the committed answer
+            execute_as(&mut app, &contract, PLAYERS[1], reveal("popcorn")).unwrap_err();
+            let err = execute_as(&mut app, &contract, PLAYERS[0], reveal("nachos")).unwrap_err();
+            assert!(err.contains("does not match"));
+            // Answers of adventures that are not open stay hidden
+            let err = execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::RevealAdventureAnswer {
+                    name: "movie".to_string(),
+                    adventure_number: 1,
+                    option: 1,
+                    salt: None,
+                },
+            )
+            .unwrap_err();
+            assert!(err.contains("not open"));
+            execute_as(&mut app, &contract, PLAYERS[0], reveal("popcorn")).unwrap();
+            let err = execute_as(&mut app, &contract, PLAYERS[0], reveal("popcorn")).unwrap_err();
+            assert!(err.contains("already been closed"));
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.adventure_winners, vec![vec!["carol".to_string()]]);
+            assert_eq!(game.adventure_rewards, vec![240]);
+        }
+
+        #[test]
+        fn abstaining_excludes_player_from_reward() {
+            let (mut app, contract) = setup_game(init_game_msg());
+            open_adventure(&mut app, &contract, 0);
+            execute_as(&mut app, &contract, "alice", vote("alice", 1)).unwrap();
+            // Changing a vote is allowed by default
+            execute_as(&mut app, &contract, "bob", vote("bob", 2)).unwrap();
+            execute_as(&mut app, &contract, "bob", vote("bob", 1)).unwrap();
+            execute_as(&mut app, &contract, "carol", vote("carol", 1)).unwrap();
+            execute_as(
+                &mut app,
+                &contract,
+                "carol",
+                ExecuteMsg::AbstainFromAdventure {
+                    name: "movie".to_string(),
+                    player: "carol".to_string(),
+                    adventure_number: 0,
+                },
+            )
+            .unwrap();
+            stop_first_adventure(&mut app, &contract).unwrap();
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.adventure_abstentions, vec![vec!["carol".to_string()]]);
+            assert!(!game.adventure_votes[0].contains_key("carol"));
+            assert_eq!(
+                game.adventure_winners,
+                vec![vec!["alice".to_string(), "bob".to_string()]]
+            );
+        }
+
+        #[test]
+        fn first_vote_counts_when_vote_change_disabled() {
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                allow_vote_change, ..
+            } = &mut init_msg
+            {
+                *allow_vote_change = Some(false);
+            }
+            let (mut app, contract) = setup_game(init_msg);
+            open_adventure(&mut app, &contract, 0);
+            // Nobody else can lock in the vote of bob
+            let err = execute_as(&mut app, &contract, USER, vote("bob", 1)).unwrap_err();
+            assert!(err.contains("only vote for themselves"));
+            execute_as(&mut app, &contract, "bob", vote("bob", 2)).unwrap();
+
+            let err = execute_as(&mut app, &contract, "bob", vote("bob", 1)).unwrap_err();
+            assert!(err.contains("already voted"));
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.adventure_votes[0].get("bob"), Some(&2));
+        }
+
+        #[test]
+        fn adventures_are_opened_and_closed_in_order() {
+            let (mut app, contract) = play_first_adventure(None);
+            let open = |adventure_number| ExecuteMsg::OpenAdventure {
+                name: "movie".to_string(),
+                adventure_number,
+                content: None,
+            };
+
+            // Only the host may open, and only one adventure at a time
+            execute_as(&mut app, &contract, PLAYERS[1], open(1)).unwrap_err();
+            execute_as(&mut app, &contract, PLAYERS[0], open(1)).unwrap_err();
+
+            // Only the host may close the adventure
+            let err = execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[1],
+                ExecuteMsg::GameAdventureStop {
+                    name: "movie".to_string(),
+                    adventure_number: 0,
+                },
+            )
+            .unwrap_err();
+            assert!(err.contains("Only the host"));
+
+            stop_first_adventure(&mut app, &contract).unwrap();
+            let err = stop_first_adventure(&mut app, &contract).unwrap_err();
+            assert!(err.contains("already been closed"));
+
+            // The game only has a single adventure
+            let err = execute_as(&mut app, &contract, PLAYERS[0], open(1)).unwrap_err();
+            assert!(err.contains("exceeds the number of adventures"));
+            let err = execute_as(&mut app, &contract, "bob", vote("bob", 1)).unwrap_err();
+            assert!(err.contains("not open for voting"));
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.current_adventure, 1);
+            assert!(!game.adventure_open);
+            assert_eq!(game.adventure_winners.len(), 1);
+        }
+
+        #[test]
+        fn adventure_without_votes_closes_as_void() {
+            let (mut app, contract) = setup_game(init_game_msg());
+            open_adventure(&mut app, &contract, 0);
+            // Option 0 stands for a void adventure and cannot be voted for
+            let err = execute_as(&mut app, &contract, PLAYERS[0], vote(PLAYERS[0], 0)).unwrap_err();
+            assert!(err.contains("option does not exist"));
+            for player in PLAYERS {
+                execute_as(
+                    &mut app,
+                    &contract,
+                    player,
+                    ExecuteMsg::AbstainFromAdventure {
+                        name: "movie".to_string(),
+                        player: player.to_string(),
+                        adventure_number: 0,
+                    },
+                )
+                .unwrap();
+            }
+            let res = stop_first_adventure(&mut app, &contract).unwrap();
+            let data: AdventureStopResponse = from_binary(&res.data.unwrap()).unwrap();
+            assert_eq!(data.winning_option, 0);
+            assert!(data.winners.is_empty());
+            assert_eq!(data.reward, 0);
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.current_adventure, 1);
+            assert_eq!(game.adventure_rewards, vec![0]);
+        }
+
+        #[test]
+        fn adventure_content_is_returned_with_results() {
+            let (mut app, contract) = setup_game(init_game_msg());
+            let content = AdventureContent {
+                prompt: "Which door does the hero open?".to_string(),
+                option_labels: vec!["Red door".to_string(), "Blue door".to_string()],
+                content_uri: Some("ipfs://bafyclip".to_string()),
+                content_hash: None,
+            };
+            execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::OpenAdventure {
+                    name: "movie".to_string(),
+                    adventure_number: 0,
+                    content: Some(content.clone()),
+                },
+            )
+            .unwrap();
+
+            // Votes must pick one of the labelled options
+            let err = execute_as(&mut app, &contract, "bob", vote("bob", 3)).unwrap_err();
+            assert!(err.contains("option does not exist"));
+            execute_as(&mut app, &contract, "bob", vote("bob", 2)).unwrap();
+            stop_first_adventure(&mut app, &contract).unwrap();
+
+            let adventure: AdventureResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::Adventure {
+                        name: "movie".to_string(),
+                        adventure_number: 0,
+                    },
+                )
+                .unwrap();
+            assert_eq!(adventure.content, Some(content));
+            assert!(!adventure.open);
+            assert_eq!(adventure.winners, Some(vec!["bob".to_string()]));
+            assert_eq!(adventure.reward, Some(240));
+        }
+
+        #[test]
+        fn winning_option_selects_next_story_scene() {
+            // Scene 0 branches to scene 1 or to an ending, scene 1 always ends
+            let story = vec![
+                StoryNode {
+                    content: None,
+                    next: vec![Some(1), None],
+                },
+                StoryNode {
+                    content: None,
+                    next: vec![None, None],
+                },
+            ];
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                story: game_story,
+                num_of_adventures,
+                ..
+            } = &mut init_msg
+            {
+                *game_story = Some(story);
+                *num_of_adventures = Some(3);
+            }
+            let (mut app, contract) = setup_game(init_msg);
+
+            // Majority picks option 1 and moves on to scene 1
+            open_adventure(&mut app, &contract, 0);
+            for (player, option) in PLAYERS.iter().zip([1, 1, 2]) {
+                execute_as(&mut app, &contract, player, vote(player, option)).unwrap();
+            }
+            stop_first_adventure(&mut app, &contract).unwrap();
+
+            // Scene 1 only has two options, and any outcome ends the story
+            open_adventure(&mut app, &contract, 1);
+            let second_vote = |player: &str, vote| ExecuteMsg::VoteForAdventure {
+                name: "movie".to_string(),
+                player: player.to_string(),
+                adventure_number: 1,
+                vote,
+            };
+            execute_as(&mut app, &contract, "carol", second_vote("carol", 3)).unwrap_err();
+            execute_as(&mut app, &contract, "carol", second_vote("carol", 2)).unwrap();
+            execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::GameAdventureStop {
+                    name: "movie".to_string(),
+                    adventure_number: 1,
+                },
+            )
+            .unwrap();
+
+            let path: StoryPathResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::StoryPath {
+                        name: "movie".to_string(),
+                    },
+                )
+                .unwrap();
+            assert!(path.finished);
+            assert_eq!(
+                path.steps
+                    .iter()
+                    .map(|step| (step.node, step.winning_option))
+                    .collect::<Vec<_>>(),
+                vec![(0, Some(1)), (1, Some(2))]
+            );
+
+            // Reaching the ending finished the game before the third adventure
+            let game = load_game(&app, &contract, "movie");
+            assert!(game.ended);
+            assert_eq!(game.winner, "carol");
+        }
+
+        #[test]
+        fn host_judged_story_answers_must_be_scene_options() {
+            let story = vec![
+                StoryNode {
+                    content: None,
+                    next: vec![Some(1), None],
+                },
+                StoryNode {
+                    content: None,
+                    next: vec![None, None],
+                },
+            ];
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                story: game_story,
+                num_of_adventures,
+                scoring_rule,
+                ..
+            } = &mut init_msg
+            {
+                *game_story = Some(story);
+                *num_of_adventures = Some(2);
+                *scoring_rule = Some(ScoringRule::HostJudged);
+            }
+            let (mut app, contract) = setup_game(init_msg);
+            open_adventure(&mut app, &contract, 0);
+            execute_as(&mut app, &contract, "bob", vote("bob", 1)).unwrap();
+
+            let reveal = |option| ExecuteMsg::RevealAdventureAnswer {
+                name: "movie".to_string(),
+                adventure_number: 0,
+                option,
+                salt: None,
+            };
+            for option in [0, 3] {
+                let err = execute_as(&mut app, &contract, PLAYERS[0], reveal(option)).unwrap_err();
+                assert!(err.contains("option does not exist"));
+            }
+            execute_as(&mut app, &contract, PLAYERS[0], reveal(1)).unwrap();
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.adventure_winners, vec![vec!["bob".to_string()]]);
+            assert_eq!(game.story_path, vec![0, 1]);
+        }
+
+        #[test]
+        fn games_are_created_from_story_templates() {
+            let (mut app, contract) = proper_instantiate();
+            let publish = |template_id, num_of_adventures| ExecuteMsg::PublishTemplate {
+                template_id,
+                title: "Haunted cinema".to_string(),
+                story: vec![StoryNode {
+                    content: None,
+                    next: vec![None, None],
+                }],
+                num_of_adventures,
+                scoring_rule: Some(ScoringRule::Minority),
+                allow_vote_change: None,
+                buy_in: None,
+            };
+
+            // Creators must be approved by the owner
+            let err = execute_as(&mut app, &contract, "dave", publish(None, 1)).unwrap_err();
+            assert!(err.contains("approved creators"));
+            execute_as(
+                &mut app,
+                &contract,
+                ADMIN,
+                ExecuteMsg::ApproveTemplateCreator {
+                    address: "dave".to_string(),
+                },
+            )
+            .unwrap();
+            let res = execute_as(&mut app, &contract, "dave", publish(None, 1)).unwrap();
+            assert!(res.events.iter().any(|event| event.ty == "wasm"
+                && event
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.key == "method" && attr.value == "publish_template")));
+            execute_as(&mut app, &contract, "dave", publish(Some(0), 2)).unwrap();
+            execute_as(
+                &mut app,
+                &contract,
+                "dave",
+                ExecuteMsg::DeprecateTemplate {
+                    template_id: 0,
+                    version: 1,
+                },
+            )
+            .unwrap();
+
+            let template: TemplateResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::Template {
+                        template_id: 0,
+                        version: None,
+                    },
+                )
+                .unwrap();
+            assert_eq!(template.info.latest_version, 2);
+            assert_eq!(template.template.num_of_adventures, 2);
+
+            // The deprecated version cannot be used anymore
+            let from_template = |template_version| ExecuteMsg::InitGame {
+                name: "movie".to_string(),
+                player: PLAYERS[0].to_string(),
+                game_stake: 100,
+                num_of_adventures: None,
+                scoring_rule: None,
+                allow_vote_change: None,
+                story: None,
+                template_id: Some(0),
+                template_version,
+                buy_in: None,
+                min_players: None,
+                max_players: None,
+                private_access: None,
+                teams: None,
+                team: None,
+            };
+            let err = execute_as(&mut app, &contract, USER, from_template(Some(1))).unwrap_err();
+            assert!(err.contains("deprecated"));
+            execute(&mut app, &contract, from_template(None));
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.template_version, Some(2));
+            assert_eq!(game.num_of_adventures, 2);
+            assert_eq!(game.scoring_rule, ScoringRule::Minority);
+            assert_eq!(game.story_path, vec![0]);
+
+            // Revoked creators cannot publish new versions of their templates
+            execute_as(
+                &mut app,
+                &contract,
+                ADMIN,
+                ExecuteMsg::RevokeTemplateCreator {
+                    address: "dave".to_string(),
+                },
+            )
+            .unwrap();
+            let err = execute_as(&mut app, &contract, "dave", publish(Some(0), 3)).unwrap_err();
+            assert!(err.contains("approved creators"));
+        }
+
+        #[test]
+        fn players_must_pay_the_exact_buy_in() {
+            let (mut app, contract) = proper_instantiate();
+            let buy_in = coin(100, NATIVE_DENOM);
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                buy_in: game_buy_in,
+                ..
+            } = &mut init_msg
+            {
+                *game_buy_in = Some(buy_in.clone());
+            }
+            // The host pays the buy-in as well
+            app.execute_contract(Addr::unchecked(PLAYERS[0]), contract.addr(), &init_msg, &[])
+                .unwrap_err();
+            app.execute_contract(
+                Addr::unchecked(PLAYERS[0]),
+                contract.addr(),
+                &init_msg,
+                std::slice::from_ref(&buy_in),
+            )
+            .unwrap();
+
+            let join = |game_stake| ExecuteMsg::AddGamePlayer {
+                name: "movie".to_string(),
+                player: "bob".to_string(),
+                game_stake,
+                join_code: None,
+                team: None,
+            };
+            // Wrong amount, then the right amount
+            app.execute_contract(
+                Addr::unchecked("bob"),
+                contract.addr(),
+                &join(50),
+                &[coin(50, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            app.execute_contract(
+                Addr::unchecked("bob"),
+                contract.addr(),
+                &join(100),
+                std::slice::from_ref(&buy_in),
+            )
+            .unwrap();
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.buy_in, Some(buy_in.clone()));
+            assert_eq!(game.total_funds, 200);
+            assert_eq!(
+                app.wrap()
+                    .query_balance(contract.addr(), NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                Uint128::new(200)
+            );
+
+            // Nobody can join once the game is running
+            execute(
+                &mut app,
+                &contract,
+                ExecuteMsg::StartGame {
+                    name: "movie".to_string(),
+                },
+            );
+            let err = app
+                .execute_contract(
+                    Addr::unchecked("carol"),
+                    contract.addr(),
+                    &ExecuteMsg::AddGamePlayer {
+                        name: "movie".to_string(),
+                        player: "carol".to_string(),
+                        game_stake: 100,
+                        join_code: None,
+                        team: None,
+                    },
+                    std::slice::from_ref(&buy_in),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "Generic error: Game has already started."
+            );
+        }
+
+        #[test]
+        fn buy_in_pot_is_paid_out_when_the_game_ends() {
+            let (mut app, contract) = proper_instantiate();
+            let buy_in = coin(100, NATIVE_DENOM);
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                buy_in: game_buy_in,
+                ..
+            } = &mut init_msg
+            {
+                *game_buy_in = Some(buy_in.clone());
+            }
+            app.execute_contract(
+                Addr::unchecked(PLAYERS[0]),
+                contract.addr(),
+                &init_msg,
+                std::slice::from_ref(&buy_in),
+            )
+            .unwrap();
+            for player in &PLAYERS[1..] {
+                app.execute_contract(
+                    Addr::unchecked(*player),
+                    contract.addr(),
+                    &ExecuteMsg::AddGamePlayer {
+                        name: "movie".to_string(),
+                        player: player.to_string(),
+                        game_stake: 100,
+                        join_code: None,
+                        team: None,
+                    },
+                    std::slice::from_ref(&buy_in),
+                )
+                .unwrap();
+            }
+            execute(
+                &mut app,
+                &contract,
+                ExecuteMsg::StartGame {
+                    name: "movie".to_string(),
+                },
+            );
+            // Only the host ends the game, once every adventure was played
+            let end = ExecuteMsg::EndGame {
+                name: "movie".to_string(),
+            };
+            let err = execute_as(&mut app, &contract, PLAYERS[0], end.clone()).unwrap_err();
+            assert!(err.contains("Every adventure must be played"));
+            open_adventure(&mut app, &contract, 0);
+            for (player, option) in PLAYERS.iter().zip([1, 1, 2]) {
+                execute_as(&mut app, &contract, player, vote(player, option)).unwrap();
+            }
+            let err = execute_as(&mut app, &contract, PLAYERS[0], end.clone()).unwrap_err();
+            assert!(err.contains("has not been closed"));
+            stop_first_adventure(&mut app, &contract).unwrap();
+            let err = execute_as(&mut app, &contract, USER, end.clone()).unwrap_err();
+            assert_eq!(err, "Generic error: Only the host can end the game.");
+            execute_as(&mut app, &contract, PLAYERS[0], end).unwrap();
+
+            // Alice and bob split the 240 adventure reward, and bob also
+            // takes the 60 winning reward
+            for player in &PLAYERS[..2] {
+                execute_as(&mut app, &contract, player, ExecuteMsg::Claim {}).unwrap();
+            }
+            execute_as(&mut app, &contract, PLAYERS[2], ExecuteMsg::Claim {}).unwrap_err();
+            let balance = |app: &App, address: &str| {
+                app.wrap()
+                    .query_balance(address, NATIVE_DENOM)
+                    .unwrap()
+                    .amount
+                    .u128()
+            };
+            assert_eq!(balance(&app, "alice"), 1020);
+            assert_eq!(balance(&app, "bob"), 1080);
+            assert_eq!(balance(&app, "carol"), 900);
+            assert_eq!(balance(&app, contract.addr().as_str()), 0);
+        }
+
+        #[test]
+        fn games_without_buy_in_refuse_funds() {
+            let (mut app, contract) = proper_instantiate();
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(PLAYERS[0]),
+                    contract.addr(),
+                    &init_game_msg(),
+                    &[coin(100, NATIVE_DENOM)],
+                )
+                .unwrap_err();
+            assert!(err.root_cause().to_string().contains("no buy-in"));
+            execute(&mut app, &contract, init_game_msg());
+
+            app.execute_contract(
+                Addr::unchecked("bob"),
+                contract.addr(),
+                &ExecuteMsg::AddGamePlayer {
+                    name: "movie".to_string(),
+                    player: "bob".to_string(),
+                    game_stake: 100,
+                    join_code: None,
+                    team: None,
+                },
+                &[coin(100, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                app.wrap()
+                    .query_balance(contract.addr(), NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                Uint128::zero()
+            );
+        }
+
+        #[test]
+        fn player_limits_are_enforced() {
+            let (mut app, contract) = proper_instantiate();
+            let with_limits = |min, max| {
+                let mut init_msg = init_game_msg();
+                if let ExecuteMsg::InitGame {
+                    min_players,
+                    max_players,
+                    ..
+                } = &mut init_msg
+                {
+                    *min_players = Some(min);
+                    *max_players = Some(max);
+                }
+                init_msg
+            };
+            let err = execute_as(&mut app, &contract, USER, with_limits(3, 2)).unwrap_err();
+            assert!(err.contains("Player limits"));
+            execute_as(
+                &mut app,
+                &contract,
+                USER,
+                with_limits(3, crate::contract::MAX_PLAYERS_PER_GAME + 1),
+            )
+            .unwrap_err();
+            execute(&mut app, &contract, with_limits(3, 3));
+
+            let join = |player: &str| ExecuteMsg::AddGamePlayer {
+                name: "movie".to_string(),
+                player: player.to_string(),
+                game_stake: 100,
+                join_code: None,
+                team: None,
+            };
+            let start = ExecuteMsg::StartGame {
+                name: "movie".to_string(),
+            };
+            execute(&mut app, &contract, join("bob"));
+            let err = execute_as(&mut app, &contract, USER, start.clone()).unwrap_err();
+            assert!(err.contains("less than 3 players"));
+            execute(&mut app, &contract, join("carol"));
+            let err = execute_as(&mut app, &contract, USER, join("dave")).unwrap_err();
+            assert!(err.contains("full"));
+            execute(&mut app, &contract, start);
+        }
+
+        #[test]
+        fn private_games_need_an_invite_or_join_code() {
+            let (mut app, contract) = proper_instantiate();
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame { private_access, .. } = &mut init_msg {
+                *private_access = Some(PrivateGameAccess {
+                    invite_list: vec!["bob".to_string()],
+                    join_code_hash: Some(sha256_hex("let-me-in")),
+                });
+            }
+            execute(&mut app, &contract, init_msg);
+
+            let join = |player: &str, join_code: Option<&str>| ExecuteMsg::AddGamePlayer {
+                name: "movie".to_string(),
+                player: player.to_string(),
+                game_stake: 100,
+                join_code: join_code.map(str::to_string),
+                team: None,
+            };
+            execute(&mut app, &contract, join("bob", None));
+            let err =
+                execute_as(&mut app, &contract, USER, join("carol", Some("guess"))).unwrap_err();
+            assert!(err.contains("private"));
+            execute(&mut app, &contract, join("carol", Some("let-me-in")));
+
+            // Private games are hidden from listings unless asked for
+            let list = |include_private| -> ListGamesResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::ListGames {
+                            start_after: None,
+                            limit: None,
+                            include_private,
+                            min_rating: None,
+                            max_rating: None,
+                        },
+                    )
+                    .unwrap()
+            };
+            assert!(list(None).games.is_empty());
+            let games = list(Some(true)).games;
+            assert_eq!(games.len(), 1);
+            assert!(games[0].private);
+            assert_eq!(games[0].num_of_players, 3);
+
+            // The invite list and join code hash are not exposed by queries
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(
+                game.private_access,
+                Some(PrivateGameAccess {
+                    invite_list: vec![],
+                    join_code_hash: None,
+                })
+            );
+        }
+
+        #[test]
+        fn host_manages_the_roster() {
+            let (mut app, contract) = proper_instantiate();
+            let buy_in = coin(100, NATIVE_DENOM);
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                buy_in: game_buy_in,
+                ..
+            } = &mut init_msg
+            {
+                *game_buy_in = Some(buy_in.clone());
+            }
+            let join = ExecuteMsg::AddGamePlayer {
+                name: "movie".to_string(),
+                player: "bob".to_string(),
+                game_stake: 100,
+                join_code: None,
+                team: None,
+            };
+            for (player, msg) in [(PLAYERS[0], &init_msg), ("bob", &join)] {
+                app.execute_contract(
+                    Addr::unchecked(player),
+                    contract.addr(),
+                    msg,
+                    std::slice::from_ref(&buy_in),
+                )
+                .unwrap();
+            }
+
+            // Only the host may ban, and banning refunds the buy-in
+            let ban = ExecuteMsg::BanPlayer {
+                name: "movie".to_string(),
+                player: "bob".to_string(),
+            };
+            execute_as(&mut app, &contract, "bob", ban.clone()).unwrap_err();
+            let res = execute_as(&mut app, &contract, PLAYERS[0], ban).unwrap();
+            assert!(res.events.iter().any(|event| event.ty == "wasm-moviemagic"
+                && event
+                    .attributes
+                    .iter()
+                    .any(|attr| attr.value == "player_banned")));
+            let balance = app.wrap().query_balance("bob", NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::new(1000));
+            let err = app
+                .execute_contract(
+                    Addr::unchecked("bob"),
+                    contract.addr(),
+                    &join,
+                    std::slice::from_ref(&buy_in),
+                )
+                .unwrap_err();
+            assert!(err.root_cause().to_string().contains("banned"));
+
+            // The host role can only go to another player
+            let transfer = |new_host: &str| ExecuteMsg::TransferHost {
+                name: "movie".to_string(),
+                new_host: new_host.to_string(),
+            };
+            execute_as(&mut app, &contract, PLAYERS[0], transfer("bob")).unwrap_err();
+            let join_carol = ExecuteMsg::AddGamePlayer {
+                name: "movie".to_string(),
+                player: "carol".to_string(),
+                game_stake: 100,
+                join_code: None,
+                team: None,
+            };
+            app.execute_contract(
+                Addr::unchecked("carol"),
+                contract.addr(),
+                &join_carol,
+                std::slice::from_ref(&buy_in),
+            )
+            .unwrap();
+            execute_as(&mut app, &contract, PLAYERS[0], transfer("carol")).unwrap();
+
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.host, "carol");
+            assert_eq!(game.players, vec!["alice".to_string(), "carol".to_string()]);
+            assert_eq!(game.banned_players, vec!["bob".to_string()]);
+            assert_eq!(game.total_funds, 200);
+        }
+
+        #[test]
+        fn ownership_is_transferred_in_two_steps() {
+            let (mut app, contract) = proper_instantiate();
+            let config = |app: &App| -> Config {
+                app.wrap()
+                    .query_wasm_smart(contract.addr(), &QueryMsg::Config {})
+                    .unwrap()
+            };
+
+            let propose = ExecuteMsg::ProposeOwner {
+                new_owner: "dave".to_string(),
+            };
+            execute_as(&mut app, &contract, USER, propose.clone()).unwrap_err();
+            execute_as(&mut app, &contract, ADMIN, propose).unwrap();
+            assert_eq!(config(&app).pending_owner, Some(Addr::unchecked("dave")));
+            assert_eq!(config(&app).owner, Some(Addr::unchecked(ADMIN)));
+
+            execute_as(&mut app, &contract, USER, ExecuteMsg::AcceptOwnership {}).unwrap_err();
+            execute_as(&mut app, &contract, "dave", ExecuteMsg::AcceptOwnership {}).unwrap();
+            let update = ExecuteMsg::UpdateConfig {
+                fee_bps: Some(500),
+                max_players_per_game: Some(3),
+                allowed_denoms: Some(vec![NATIVE_DENOM.to_string()]),
+                season_pool_bps: None,
+                season_payouts: None,
+            };
+            execute_as(&mut app, &contract, ADMIN, update.clone()).unwrap_err();
+            execute_as(&mut app, &contract, "dave", update).unwrap();
+
+            let config = config(&app);
+            assert_eq!(config.owner, Some(Addr::unchecked("dave")));
+            assert_eq!(config.pending_owner, None);
+            assert_eq!(config.fee_bps, 500);
+
+            // The fee comes off the pot before the adventure funds are set
+            let (mut app, contract) = setup_game_with(app, contract, init_game_msg());
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.max_players, 3);
+            assert_eq!(game.fee_amount, 15);
+            assert_eq!(game.adventure_funds, 228);
+
+            execute_as(
+                &mut app,
+                &contract,
+                "dave",
+                ExecuteMsg::RenounceOwnership {},
+            )
+            .unwrap();
+            let config: Config = app
+                .wrap()
+                .query_wasm_smart(contract.addr(), &QueryMsg::Config {})
+                .unwrap();
+            assert_eq!(config.owner, None);
+        }
+
+        #[test]
+        fn games_are_started_only_once() {
+            let (mut app, contract) = proper_instantiate();
+            execute_as(
+                &mut app,
+                &contract,
+                ADMIN,
+                ExecuteMsg::UpdateConfig {
+                    fee_bps: Some(1000),
+                    max_players_per_game: None,
+                    allowed_denoms: None,
+                    season_pool_bps: None,
+                    season_payouts: None,
+                },
+            )
+            .unwrap();
+            let buy_in = coin(100, NATIVE_DENOM);
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                buy_in: game_buy_in,
+                ..
+            } = &mut init_msg
+            {
+                *game_buy_in = Some(buy_in.clone());
+            }
+            let mut msgs = vec![(PLAYERS[0], init_msg)];
+            for player in &PLAYERS[1..] {
+                let join = ExecuteMsg::AddGamePlayer {
+                    name: "movie".to_string(),
+                    player: player.to_string(),
+                    game_stake: 100,
+                    join_code: None,
+                    team: None,
+                };
+                msgs.push((player, join));
+            }
+            for (sender, msg) in msgs {
+                app.execute_contract(
+                    Addr::unchecked(sender),
+                    contract.addr(),
+                    &msg,
+                    std::slice::from_ref(&buy_in),
+                )
+                .unwrap();
+            }
+
+            let start = ExecuteMsg::StartGame {
+                name: "movie".to_string(),
+            };
+            execute(&mut app, &contract, start.clone());
+            let err = execute_as(&mut app, &contract, USER, start).unwrap_err();
+            assert_eq!(err, "Generic error: Game has already started.");
+
+            // The fee left the contract once
+            let balance = app
+                .wrap()
+                .query_balance(contract.addr(), NATIVE_DENOM)
+                .unwrap();
+            assert_eq!(balance.amount, Uint128::new(270));
+        }
+
+        #[test]
+        fn migrate_backfills_the_config_of_old_deployments() {
+            let mut app = mock_app();
+            let code_id = app.store_code(contract_template());
+            let contract_addr = app
+                .instantiate_contract(
+                    code_id,
+                    Addr::unchecked(ADMIN),
+                    &InstantiateMsg { count: 1i32 },
+                    &[],
+                    "test",
+                    Some(ADMIN.to_string()),
+                )
+                .unwrap();
+
+            // Rewrite the storage the way deployments from before the config left it
+            app.init_modules(|_, _, storage| {
+                let mut namespace = b"contract_data/".to_vec();
+                namespace.extend_from_slice(contract_addr.as_bytes());
+                let mut storage = PrefixedStorage::multilevel(storage, &[b"wasm", &namespace]);
+                let game = |name: &str, players: &str| {
+                    format!(
+                        r#"{{"name":"{}","players":{},"total_funds":200,"adventure_funds":160,"initiated":true,"started":true,"ended":false,"winner":"","winning_reward":0,"adventure_votes":[],"adventure_winners":[],"adventure_rewards":[],"adventure_winning_votes":[],"num_of_adventures":1}}"#,
+                        name, players
+                    )
+                };
+                let state = format!(
+                    r#"{{"games":[{},{}],"owner":"carol"}}"#,
+                    game("movie", r#"["alice","bob"]"#),
+                    game("sequel", r#"["bob","carol"]"#)
+                );
+                storage.set(b"state", state.as_bytes());
+                storage.remove(b"config");
+            });
+            let config = |app: &App| -> StdResult<Config> {
+                app.wrap()
+                    .query_wasm_smart(&contract_addr, &QueryMsg::Config {})
+            };
+            assert!(config(&app).is_err());
+
+            app.migrate_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &MigrateMsg {},
+                code_id,
+            )
+            .unwrap();
+            let config = config(&app).unwrap();
+            assert_eq!(config.owner, Some(Addr::unchecked("carol")));
+            assert_eq!(config.max_players_per_game, MAX_PLAYERS_PER_GAME);
+
+            // Old games get their id, host and player index back
+            let contract = CwTemplateContract(contract_addr);
+            let sequel = load_game(&app, &contract, "sequel");
+            assert_eq!(sequel.id, 1);
+            assert_eq!(sequel.host, "bob");
+            let res: PlayerGamesResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::PlayerGames {
+                        address: "bob".to_string(),
+                        start_after: None,
+                        limit: None,
+                        phase: None,
+                    },
+                )
+                .unwrap();
+            let names: Vec<_> = res.games.iter().map(|game| &game.game.name).collect();
+            assert_eq!(names, vec!["movie", "sequel"]);
+            for msg in [
+                ExecuteMsg::OpenAdventure {
+                    name: "sequel".to_string(),
+                    adventure_number: 0,
+                    content: None,
+                },
+                ExecuteMsg::GameAdventureStop {
+                    name: "sequel".to_string(),
+                    adventure_number: 0,
+                },
+            ] {
+                execute_as(&mut app, &contract, "bob", msg).unwrap();
+            }
+            assert_eq!(load_game(&app, &contract, "sequel").current_adventure, 1);
+            assert_eq!(load_game(&app, &contract, "movie").current_adventure, 0);
+        }
+
+        #[test]
+        fn pause_blocks_games_votes_and_payouts() {
+            let (mut app, contract) = setup_game(init_game_msg());
+            open_adventure(&mut app, &contract, 0);
+            execute_as(
+                &mut app,
+                &contract,
+                ADMIN,
+                ExecuteMsg::SetGuardian {
+                    guardian: Some("guardian".to_string()),
+                },
+            )
+            .unwrap();
+            execute_as(&mut app, &contract, USER, ExecuteMsg::Pause {}).unwrap_err();
+            execute_as(&mut app, &contract, "guardian", ExecuteMsg::Pause {}).unwrap();
+
+            let paused: PausedResponse = app
+                .wrap()
+                .query_wasm_smart(contract.addr(), &QueryMsg::Paused {})
+                .unwrap();
+            assert!(paused.paused);
+            let err = execute_as(&mut app, &contract, "bob", vote("bob", 1)).unwrap_err();
+            assert_eq!(err, ContractError::Paused {}.to_string());
+            let mut other_game = init_game_msg();
+            if let ExecuteMsg::InitGame { name, .. } = &mut other_game {
+                *name = "sequel".to_string();
+            }
+            execute_as(&mut app, &contract, USER, other_game).unwrap_err();
+            // Nothing is paid out while paused
+            let err = stop_first_adventure(&mut app, &contract).unwrap_err();
+            assert_eq!(err, ContractError::Paused {}.to_string());
+            let err = execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::EndGame {
+                    name: "movie".to_string(),
+                },
+            )
+            .unwrap_err();
+            assert_eq!(err, ContractError::Paused {}.to_string());
+
+            execute_as(&mut app, &contract, ADMIN, ExecuteMsg::Unpause {}).unwrap();
+            execute_as(&mut app, &contract, "bob", vote("bob", 1)).unwrap();
+            stop_first_adventure(&mut app, &contract).unwrap();
+        }
+
+        #[test]
+        fn state_transitions_emit_events() {
+            let (mut app, contract) = proper_instantiate();
+            let res = execute_as(&mut app, &contract, USER, init_game_msg()).unwrap();
+            assert_eq!(
+                event_attr(&res, "game_created", "host"),
+                Some("alice".to_string())
+            );
+            assert_eq!(
+                event_attr(&res, "game_created", "scoring_rule"),
+                Some("majority".to_string())
+            );
+            let res = execute_as(
+                &mut app,
+                &contract,
+                USER,
+                ExecuteMsg::AddGamePlayer {
+                    name: "movie".to_string(),
+                    player: "bob".to_string(),
+                    game_stake: 100,
+                    join_code: None,
+                    team: None,
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                event_attr(&res, "player_joined", "player"),
+                Some("bob".to_string())
+            );
+            let res = execute_as(
+                &mut app,
+                &contract,
+                USER,
+                ExecuteMsg::StartGame {
+                    name: "movie".to_string(),
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                event_attr(&res, "game_started", "total_funds"),
+                Some("200".to_string())
+            );
+            assert_eq!(
+                event_attr(&res, "game_started", "game_id"),
+                Some("0".to_string())
+            );
+
+            open_adventure(&mut app, &contract, 0);
+            let res = execute_as(&mut app, &contract, "alice", vote("alice", 2)).unwrap();
+            assert_eq!(event_attr(&res, "vote_cast", "vote"), Some("2".to_string()));
+            execute_as(&mut app, &contract, "bob", vote("bob", 2)).unwrap();
+
+            let res = stop_first_adventure(&mut app, &contract).unwrap();
+            assert_eq!(
+                event_attr(&res, "adventure_closed", "winning_option"),
+                Some("2".to_string())
+            );
+            assert_eq!(
+                event_attr(&res, "adventure_closed", "winners"),
+                Some("alice,bob".to_string())
+            );
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(
+                event_attr(&res, "adventure_closed", "reward"),
+                Some(game.adventure_rewards[0].to_string())
+            );
+
+            let res = execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::EndGame {
+                    name: "movie".to_string(),
+                },
+            )
+            .unwrap();
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(event_attr(&res, "game_ended", "winner"), Some(game.winner));
+            assert_eq!(
+                event_attr(&res, "game_ended", "reward"),
+                Some(game.winning_reward.to_string())
+            );
+        }
+
+        #[test]
+        fn execute_calls_return_data() {
+            let (mut app, contract) = proper_instantiate();
+            let mut other_game = init_game_msg();
+            if let ExecuteMsg::InitGame { name, .. } = &mut other_game {
+                *name = "sequel".to_string();
+            }
+            let res = execute_as(&mut app, &contract, USER, other_game).unwrap();
+            let data: InitGameResponse = from_binary(&res.data.unwrap()).unwrap();
+            assert_eq!(data.game_id, 0);
+            let res = execute_as(&mut app, &contract, USER, init_game_msg()).unwrap();
+            let data: InitGameResponse = from_binary(&res.data.unwrap()).unwrap();
+            assert_eq!(data.game_id, 1);
+            for player in &PLAYERS[1..] {
+                execute(
+                    &mut app,
+                    &contract,
+                    ExecuteMsg::AddGamePlayer {
+                        name: "movie".to_string(),
+                        player: player.to_string(),
+                        game_stake: 100,
+                        join_code: None,
+                        team: None,
+                    },
+                );
+            }
+            execute(
+                &mut app,
+                &contract,
+                ExecuteMsg::StartGame {
+                    name: "movie".to_string(),
+                },
+            );
+
+            open_adventure(&mut app, &contract, 0);
+            for (player, option) in PLAYERS.iter().zip([1, 2, 2]) {
+                execute_as(&mut app, &contract, player, vote(player, option)).unwrap();
+            }
+            let res = stop_first_adventure(&mut app, &contract).unwrap();
+            let data: AdventureStopResponse = from_binary(&res.data.unwrap()).unwrap();
+            assert_eq!(
+                data.tally,
+                vec![
+                    OptionTally {
+                        option: 1,
+                        votes: 1,
+                        weight: 1,
+                    },
+                    OptionTally {
+                        option: 2,
+                        votes: 2,
+                        weight: 2,
+                    },
+                ]
+            );
+            assert_eq!(data.winning_option, 2);
+            assert_eq!(data.winners.len(), 2);
+            assert_eq!(data.game_result, None);
+
+            let res = execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::EndGame {
+                    name: "movie".to_string(),
+                },
+            )
+            .unwrap();
+            let data: EndGameResponse = from_binary(&res.data.unwrap()).unwrap();
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(data.winner, game.winner);
+            assert_eq!(data.standings.len(), 3);
+            assert_eq!(data.standings[0].player, game.winner);
+            assert_eq!(
+                data.standings[0].rewards,
+                game.adventure_rewards[0] + game.winning_reward
+            );
+            assert_eq!(data.standings[2].player, "alice");
+            assert_eq!(data.standings[2].rewards, 0);
+        }
+
+        #[test]
+        fn player_games_are_indexed_by_address() {
+            let (mut app, contract) = play_first_adventure(None);
+            stop_first_adventure(&mut app, &contract).unwrap();
+            let mut sequel = init_game_msg();
+            if let ExecuteMsg::InitGame { name, player, .. } = &mut sequel {
+                *name = "sequel".to_string();
+                *player = "carol".to_string();
+            }
+            execute(&mut app, &contract, sequel);
+            let join = |player: &str| ExecuteMsg::AddGamePlayer {
+                name: "sequel".to_string(),
+                player: player.to_string(),
+                game_stake: 100,
+                join_code: None,
+                team: None,
+            };
+            execute(&mut app, &contract, join("bob"));
+            execute(&mut app, &contract, join("dave"));
+            execute_as(
+                &mut app,
+                &contract,
+                "carol",
+                ExecuteMsg::RemovePlayer {
+                    name: "sequel".to_string(),
+                    player: "dave".to_string(),
+                },
+            )
+            .unwrap();
+
+            let player_games = |app: &App, address: &str, phase: Option<GamePhase>| {
+                let res: PlayerGamesResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::PlayerGames {
+                            address: address.to_string(),
+                            start_after: None,
+                            limit: None,
+                            phase,
+                        },
+                    )
+                    .unwrap();
+                res.games
+            };
+            let games = player_games(&app, "bob", None);
+            assert_eq!(games.len(), 2);
+            assert_eq!(games[0].game.name, "movie");
+            assert_eq!(games[0].stake, 100);
+            assert_eq!(games[0].votes, vec![Some(1)]);
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(games[0].winnings, game.adventure_rewards[0]);
+            assert_eq!(games[1].game.name, "sequel");
+            assert_eq!(games[1].votes, vec![]);
+
+            let games = player_games(&app, "bob", Some(GamePhase::Open));
+            assert_eq!(games.len(), 1);
+            assert_eq!(games[0].game.id, 1);
+            assert!(player_games(&app, "dave", None).is_empty());
+        }
+
+        #[test]
+        fn tally_projects_rewards_while_open() {
+            let tally = |app: &App, contract: &CwTemplateContract| {
+                let res: AdventureTallyResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::AdventureTally {
+                            name: "movie".to_string(),
+                            adventure_number: 0,
+                        },
+                    )
+                    .unwrap();
+                res
+            };
+
+            let (mut app, contract) = setup_game(init_game_msg());
+            open_adventure(&mut app, &contract, 0);
+            execute_as(&mut app, &contract, "alice", vote("alice", 2)).unwrap();
+            let res = tally(&app, &contract);
+            assert!(res.open);
+            assert_eq!(res.yet_to_vote, 2);
+            assert_eq!(res.projected_winning_option, Some(2));
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(res.projected_reward, Some(game.adventure_funds));
+            execute_as(&mut app, &contract, "bob", vote("bob", 2)).unwrap();
+            assert_eq!(
+                tally(&app, &contract).projected_reward,
+                Some(game.adventure_funds / 2)
+            );
+
+            // The winner of a host-judged adventure stays hidden until revealed
+            let (mut app, contract) = play_first_adventure(Some(ScoringRule::HostJudged));
+            let res = tally(&app, &contract);
+            assert_eq!(res.tally.len(), 2);
+            assert_eq!(res.yet_to_vote, 0);
+            assert_eq!(res.projected_winning_option, None);
+            assert_eq!(res.projected_reward, None);
+            execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::RevealAdventureAnswer {
+                    name: "movie".to_string(),
+                    adventure_number: 0,
+                    option: 2,
+                    salt: Some("popcorn".to_string()),
+                },
+            )
+            .unwrap();
+            let res = tally(&app, &contract);
+            assert_eq!(res.projected_winning_option, Some(2));
+            assert_eq!(res.projected_winners, 1);
+        }
 
         #[test]
-        fn count() {
-            let (mut app, cw_template_contract) = proper_instantiate();
+        fn simulated_payout_matches_the_real_one() {
+            let (mut app, contract) = setup_game(init_game_msg());
+            open_adventure(&mut app, &contract, 0);
+            execute_as(&mut app, &contract, "alice", vote("alice", 1)).unwrap();
+            execute_as(&mut app, &contract, "bob", vote("bob", 2)).unwrap();
+            let simulate = |app: &App, option: u32| {
+                let res: SimulatePayoutResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::SimulatePayout {
+                            name: "movie".to_string(),
+                            adventure_number: 0,
+                            player: "carol".to_string(),
+                            option,
+                        },
+                    )
+                    .unwrap();
+                res
+            };
+
+            let game = load_game(&app, &contract, "movie");
+            let res = simulate(&app, 1);
+            assert_eq!(res.winning_option, Some(1));
+            assert_eq!(res.payout, game.adventure_funds / 2);
+            // A three way tie goes to the highest option
+            assert_eq!(simulate(&app, 3).payout, game.adventure_funds);
+
+            let simulated = simulate(&app, 2);
+            execute_as(&mut app, &contract, "carol", vote("carol", 2)).unwrap();
+            let res = stop_first_adventure(&mut app, &contract).unwrap();
+            let data: AdventureStopResponse = from_binary(&res.data.unwrap()).unwrap();
+            assert_eq!(simulated.winning_option, Some(data.winning_option));
+            assert_eq!(simulated.winners, data.winners.len() as u32);
+            assert_eq!(simulated.payout, data.reward);
+        }
+
+        #[test]
+        fn simulated_payout_respects_the_vote_change_policy() {
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                allow_vote_change, ..
+            } = &mut init_msg
+            {
+                *allow_vote_change = Some(false);
+            }
+            let (mut app, contract) = setup_game(init_msg);
+            open_adventure(&mut app, &contract, 0);
+            execute_as(
+                &mut app,
+                &contract,
+                "carol",
+                ExecuteMsg::AbstainFromAdventure {
+                    name: "movie".to_string(),
+                    player: "carol".to_string(),
+                    adventure_number: 0,
+                },
+            )
+            .unwrap();
+
+            // Carol cannot vote after abstaining, so nothing can be simulated
+            execute_as(&mut app, &contract, "carol", vote("carol", 1)).unwrap_err();
+            let err = app
+                .wrap()
+                .query_wasm_smart::<SimulatePayoutResponse>(
+                    contract.addr(),
+                    &QueryMsg::SimulatePayout {
+                        name: "movie".to_string(),
+                        adventure_number: 0,
+                        player: "carol".to_string(),
+                        option: 1,
+                    },
+                )
+                .unwrap_err();
+            assert!(err.to_string().contains("already voted"));
+
+            // Nor can a vote for an option that does not exist
+            let err = app
+                .wrap()
+                .query_wasm_smart::<SimulatePayoutResponse>(
+                    contract.addr(),
+                    &QueryMsg::SimulatePayout {
+                        name: "movie".to_string(),
+                        adventure_number: 0,
+                        player: "bob".to_string(),
+                        option: 0,
+                    },
+                )
+                .unwrap_err();
+            assert!(err.to_string().contains("option does not exist"));
+        }
+
+        #[test]
+        fn leaderboard_ranks_players_across_games() {
+            let (mut app, contract) = play_first_adventure(None);
+            stop_first_adventure(&mut app, &contract).unwrap();
+            execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::EndGame {
+                    name: "movie".to_string(),
+                },
+            )
+            .unwrap();
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.winner, "bob");
+
+            let leaderboard = |app: &App, sort_by, start_after: Option<&str>, limit| {
+                let res: LeaderboardResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::Leaderboard {
+                            sort_by,
+                            start_after: start_after.map(str::to_string),
+                            limit,
+                            season_id: None,
+                        },
+                    )
+                    .unwrap();
+                res.entries
+                    .into_iter()
+                    .map(|entry| entry.address)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                leaderboard(&app, None, None, None),
+                vec!["bob", "alice", "carol"]
+            );
+            assert_eq!(
+                leaderboard(&app, Some(LeaderboardSort::Earnings), Some("bob"), Some(1)),
+                vec!["alice"]
+            );
+
+            let bob: LeaderboardEntry = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::PlayerStats {
+                        address: "bob".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(bob.stats.games_played, 1);
+            assert_eq!(bob.stats.games_won, 1);
+            assert_eq!(bob.stats.adventures_won, 1);
+            assert_eq!(bob.stats.total_staked, 100);
+            assert_eq!(
+                bob.stats.total_earned,
+                game.adventure_rewards[0] + game.winning_reward
+            );
+            let carol: LeaderboardEntry = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::PlayerStats {
+                        address: "carol".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(carol.net_pnl, -100);
+
+            // Alice catches up with bob in a sequel and moves up once, ahead
+            // of him on the address order
+            let mut sequel = init_game_msg();
+            if let ExecuteMsg::InitGame { name, player, .. } = &mut sequel {
+                *name = "sequel".to_string();
+                *player = "carol".to_string();
+            }
+            let vote_in_sequel = |player: &str, vote| ExecuteMsg::VoteForAdventure {
+                name: "sequel".to_string(),
+                player: player.to_string(),
+                adventure_number: 0,
+                vote,
+            };
+            let msgs = [
+                (USER, sequel),
+                (
+                    USER,
+                    ExecuteMsg::AddGamePlayer {
+                        name: "sequel".to_string(),
+                        player: "alice".to_string(),
+                        game_stake: 100,
+                        join_code: None,
+                        team: None,
+                    },
+                ),
+                (
+                    USER,
+                    ExecuteMsg::StartGame {
+                        name: "sequel".to_string(),
+                    },
+                ),
+                (
+                    "carol",
+                    ExecuteMsg::OpenAdventure {
+                        name: "sequel".to_string(),
+                        adventure_number: 0,
+                        content: None,
+                    },
+                ),
+                ("carol", vote_in_sequel("carol", 1)),
+                ("alice", vote_in_sequel("alice", 2)),
+                (
+                    "carol",
+                    ExecuteMsg::GameAdventureStop {
+                        name: "sequel".to_string(),
+                        adventure_number: 0,
+                    },
+                ),
+                (
+                    "carol",
+                    ExecuteMsg::EndGame {
+                        name: "sequel".to_string(),
+                    },
+                ),
+            ];
+            for (sender, msg) in msgs {
+                execute_as(&mut app, &contract, sender, msg).unwrap();
+            }
+            assert_eq!(
+                leaderboard(&app, None, None, None),
+                vec!["alice", "bob", "carol"]
+            );
+        }
+
+        #[test]
+        fn ratings_follow_the_final_ranking() {
+            let (mut app, contract) = play_first_adventure(None);
+            stop_first_adventure(&mut app, &contract).unwrap();
+            execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::EndGame {
+                    name: "movie".to_string(),
+                },
+            )
+            .unwrap();
+            let rating = |app: &App, address: &str| {
+                let res: PlayerRating = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::PlayerRating {
+                            address: address.to_string(),
+                        },
+                    )
+                    .unwrap();
+                res
+            };
+            assert_eq!(rating(&app, "alice").rating, 1208);
+            assert_eq!(rating(&app, "bob").rating, 1208);
+            assert_eq!(rating(&app, "carol").rating, 1184);
+            assert_eq!(rating(&app, "carol").games_rated, 1);
+            assert_eq!(rating(&app, "dave").rating, INITIAL_RATING);
+
+            let mut sequel = init_game_msg();
+            if let ExecuteMsg::InitGame { name, player, .. } = &mut sequel {
+                *name = "sequel".to_string();
+                *player = "carol".to_string();
+            }
+            execute(&mut app, &contract, sequel);
+            let list_games = |app: &App, min_rating, max_rating| {
+                let res: ListGamesResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::ListGames {
+                            start_after: None,
+                            limit: None,
+                            include_private: None,
+                            min_rating,
+                            max_rating,
+                        },
+                    )
+                    .unwrap();
+                res.games
+                    .into_iter()
+                    .map(|game| game.name)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(list_games(&app, Some(1190), None), vec!["movie"]);
+            assert_eq!(list_games(&app, None, Some(1190)), vec!["sequel"]);
+        }
+
+        #[test]
+        fn closing_a_season_archives_its_standings() {
+            let (mut app, contract) = proper_instantiate();
+            let now = app.block_info().time;
+            let create_season = |name: &str, start_time: Timestamp| ExecuteMsg::CreateSeason {
+                name: name.to_string(),
+                start_time,
+                end_time: start_time.plus_seconds(1000),
+            };
+            execute_as(&mut app, &contract, USER, create_season("june", now)).unwrap_err();
+            execute_as(&mut app, &contract, ADMIN, create_season("june", now)).unwrap();
+            let err = execute_as(
+                &mut app,
+                &contract,
+                ADMIN,
+                create_season("july", now.plus_seconds(999)),
+            )
+            .unwrap_err();
+            assert_eq!(
+                err,
+                "Generic error: The season overlaps with another season."
+            );
+            let err = execute_as(
+                &mut app,
+                &contract,
+                ADMIN,
+                create_season("july", now.plus_seconds(1000)),
+            )
+            .unwrap_err();
+            assert!(err.contains("once the current one has ended"));
+
+            let (mut app, contract) = setup_game_with(app, contract, init_game_msg());
+            assert_eq!(load_game(&app, &contract, "movie").season_id, Some(0));
+            open_adventure(&mut app, &contract, 0);
+            for (player, option) in PLAYERS.iter().zip([1, 1, 2]) {
+                execute_as(&mut app, &contract, player, vote(player, option)).unwrap();
+            }
+            stop_first_adventure(&mut app, &contract).unwrap();
+            execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::EndGame {
+                    name: "movie".to_string(),
+                },
+            )
+            .unwrap();
+
+            let close = ExecuteMsg::CloseSeason { season_id: 0 };
+            let err = execute_as(&mut app, &contract, ADMIN, close.clone()).unwrap_err();
+            assert_eq!(err, "Generic error: Season has not ended yet.");
+            app.update_block(|block| block.time = block.time.plus_seconds(1000));
+            execute_as(&mut app, &contract, ADMIN, close).unwrap();
+            let now = app.block_info().time;
+            execute_as(&mut app, &contract, ADMIN, create_season("july", now)).unwrap();
+
+            let season_leaderboard = |app: &App, season_id| {
+                let res: LeaderboardResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::Leaderboard {
+                            sort_by: None,
+                            start_after: None,
+                            limit: None,
+                            season_id: Some(season_id),
+                        },
+                    )
+                    .unwrap();
+                res.entries
+                    .into_iter()
+                    .map(|entry| (entry.address, entry.stats.games_won))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                season_leaderboard(&app, 0),
+                vec![
+                    ("bob".to_string(), 1),
+                    ("alice".to_string(), 0),
+                    ("carol".to_string(), 0)
+                ]
+            );
+            assert!(season_leaderboard(&app, 1).is_empty());
+            let seasons: ListSeasonsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::ListSeasons {
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap();
+            assert!(seasons.seasons[0].closed);
+            assert!(!seasons.seasons[1].closed);
+        }
+
+        // Plays a buy-in game during a season whose pool takes 10% of the pot.
+        // Alice and bob win the adventure and bob wins the game.
+        fn play_season_game(season_payouts: Vec<u16>) -> (App, CwTemplateContract) {
+            let (mut app, contract) = proper_instantiate();
+            let now = app.block_info().time;
+            for msg in [
+                ExecuteMsg::UpdateConfig {
+                    fee_bps: None,
+                    max_players_per_game: None,
+                    allowed_denoms: None,
+                    season_pool_bps: Some(1000),
+                    season_payouts: Some(season_payouts),
+                },
+                ExecuteMsg::CreateSeason {
+                    name: "june".to_string(),
+                    start_time: now,
+                    end_time: now.plus_seconds(1000),
+                },
+            ] {
+                execute_as(&mut app, &contract, ADMIN, msg).unwrap();
+            }
+
+            let buy_in = coin(100, NATIVE_DENOM);
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                buy_in: game_buy_in,
+                ..
+            } = &mut init_msg
+            {
+                *game_buy_in = Some(buy_in.clone());
+            }
+            let mut msgs = vec![(PLAYERS[0], init_msg)];
+            for player in &PLAYERS[1..] {
+                let join = ExecuteMsg::AddGamePlayer {
+                    name: "movie".to_string(),
+                    player: player.to_string(),
+                    game_stake: 100,
+                    join_code: None,
+                    team: None,
+                };
+                msgs.push((player, join));
+            }
+            for (sender, msg) in msgs {
+                app.execute_contract(
+                    Addr::unchecked(sender),
+                    contract.addr(),
+                    &msg,
+                    std::slice::from_ref(&buy_in),
+                )
+                .unwrap();
+            }
+            execute(
+                &mut app,
+                &contract,
+                ExecuteMsg::StartGame {
+                    name: "movie".to_string(),
+                },
+            );
+            open_adventure(&mut app, &contract, 0);
+            for (player, option) in PLAYERS.iter().zip([1, 1, 2]) {
+                execute_as(&mut app, &contract, player, vote(player, option)).unwrap();
+            }
+            stop_first_adventure(&mut app, &contract).unwrap();
+            execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::EndGame {
+                    name: "movie".to_string(),
+                },
+            )
+            .unwrap();
+            (app, contract)
+        }
+
+        #[test]
+        fn season_pool_is_paid_out_to_the_top_players() {
+            let (mut app, contract) = play_season_game(vec![6000, 3000]);
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.season_pool_amount, 30);
+            assert_eq!(game.adventure_funds, 216);
+
+            // Starting the game again does not fund the pool a second time
+            let start = ExecuteMsg::StartGame {
+                name: "movie".to_string(),
+            };
+            execute_as(&mut app, &contract, USER, start).unwrap_err();
+            let season: Season = app
+                .wrap()
+                .query_wasm_smart(contract.addr(), &QueryMsg::Season { season_id: 0 })
+                .unwrap();
+            assert_eq!(season.pool, vec![coin(30, NATIVE_DENOM)]);
+
+            app.update_block(|block| block.time = block.time.plus_seconds(1000));
+            execute_as(
+                &mut app,
+                &contract,
+                ADMIN,
+                ExecuteMsg::CloseSeason { season_id: 0 },
+            )
+            .unwrap();
+
+            let claimable = |app: &App, address: &str| {
+                let res: ClaimableResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        contract.addr(),
+                        &QueryMsg::Claimable {
+                            address: address.to_string(),
+                        },
+                    )
+                    .unwrap();
+                res.balances
+            };
+            // Game winnings of 162 and 108 plus season prizes of 18 and 9
+            assert_eq!(claimable(&app, "bob"), vec![coin(180, NATIVE_DENOM)]);
+            assert_eq!(claimable(&app, "alice"), vec![coin(117, NATIVE_DENOM)]);
+            assert!(claimable(&app, "carol").is_empty());
+
+            // The owner gets what the payout table leaves
+            execute_as(&mut app, &contract, ADMIN, ExecuteMsg::Claim {}).unwrap();
+            let balance = app
+                .wrap()
+                .query_balance(contract.addr(), NATIVE_DENOM)
+                .unwrap();
+            assert_eq!(balance.amount, Uint128::new(297));
+            execute_as(&mut app, &contract, "bob", ExecuteMsg::Claim {}).unwrap();
+            let balance = app.wrap().query_balance("bob", NATIVE_DENOM).unwrap();
+            assert_eq!(balance.amount, Uint128::new(1080));
+            let err = execute_as(&mut app, &contract, "bob", ExecuteMsg::Claim {}).unwrap_err();
+            assert_eq!(err, "Generic error: Nothing to claim.");
+        }
+
+        #[test]
+        fn season_pool_remainder_goes_to_the_top_player_without_an_owner() {
+            let (mut app, contract) = play_season_game(vec![6000]);
+            execute_as(&mut app, &contract, ADMIN, ExecuteMsg::RenounceOwnership {}).unwrap();
+            app.update_block(|block| block.time = block.time.plus_seconds(1000));
+            execute_as(
+                &mut app,
+                &contract,
+                "carol",
+                ExecuteMsg::CloseSeason { season_id: 0 },
+            )
+            .unwrap();
+
+            // Bob's game winnings of 162 plus the whole pool of 30
+            let res: ClaimableResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::Claimable {
+                        address: "bob".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(res.balances, vec![coin(192, NATIVE_DENOM)]);
+        }
+
+        #[test]
+        fn tournament_winners_advance_to_the_final() {
+            let (mut app, contract) = proper_instantiate();
+            let entry_fee = coin(10, NATIVE_DENOM);
+            execute_as(
+                &mut app,
+                &contract,
+                "dave",
+                ExecuteMsg::CreateTournament {
+                    name: "cup".to_string(),
+                    entry_fee: Some(entry_fee.clone()),
+                    game_size: 2,
+                    num_of_adventures: 1,
+                    scoring_rule: None,
+                },
+            )
+            .unwrap();
+            let err = app
+                .execute_contract(
+                    Addr::unchecked("alice"),
+                    contract.addr(),
+                    &ExecuteMsg::JoinTournament { tournament_id: 0 },
+                    &[coin(5, NATIVE_DENOM)],
+                )
+                .unwrap_err();
+            assert!(err
+                .root_cause()
+                .to_string()
+                .contains("entry fee of exactly"));
+            for player in PLAYERS {
+                app.execute_contract(
+                    Addr::unchecked(player),
+                    contract.addr(),
+                    &ExecuteMsg::JoinTournament { tournament_id: 0 },
+                    std::slice::from_ref(&entry_fee),
+                )
+                .unwrap();
+            }
+            // Taking the name of a tournament game does not hijack it, and
+            // game names cannot be reused
+            let mut squatter_msg = init_game_msg();
+            if let ExecuteMsg::InitGame { name, .. } = &mut squatter_msg {
+                *name = "cup round 2 game 1".to_string();
+            }
+            execute(&mut app, &contract, squatter_msg.clone());
+            let err = execute_as(&mut app, &contract, USER, squatter_msg).unwrap_err();
+            assert!(err.contains("already exists"));
+
+            let start = ExecuteMsg::StartTournament { tournament_id: 0 };
+            execute_as(&mut app, &contract, "alice", start.clone()).unwrap_err();
+            execute_as(&mut app, &contract, "dave", start).unwrap();
+
+            // The organizer hosts the games and the players vote in them
+            let play = |app: &mut App, name: &str, votes: [(&str, u32); 2]| {
+                let msgs = [
+                    (
+                        "dave",
+                        ExecuteMsg::OpenAdventure {
+                            name: name.to_string(),
+                            adventure_number: 0,
+                            content: None,
+                        },
+                    ),
+                    (
+                        votes[0].0,
+                        ExecuteMsg::VoteForAdventure {
+                            name: name.to_string(),
+                            player: votes[0].0.to_string(),
+                            adventure_number: 0,
+                            vote: votes[0].1,
+                        },
+                    ),
+                    (
+                        votes[1].0,
+                        ExecuteMsg::VoteForAdventure {
+                            name: name.to_string(),
+                            player: votes[1].0.to_string(),
+                            adventure_number: 0,
+                            vote: votes[1].1,
+                        },
+                    ),
+                    (
+                        "dave",
+                        ExecuteMsg::GameAdventureStop {
+                            name: name.to_string(),
+                            adventure_number: 0,
+                        },
+                    ),
+                    (
+                        "dave",
+                        ExecuteMsg::EndGame {
+                            name: name.to_string(),
+                        },
+                    ),
+                ];
+                for (sender, msg) in msgs {
+                    execute_as(app, &contract, sender, msg).unwrap();
+                }
+            };
+            let tournament = |app: &App| {
+                let res: TournamentResponse = app
+                    .wrap()
+                    .query_wasm_smart(contract.addr(), &QueryMsg::Tournament { tournament_id: 0 })
+                    .unwrap();
+                res
+            };
+
+            let res = tournament(&app);
+            assert_eq!(res.bracket.len(), 1);
+            assert_eq!(res.bracket[0].games[0].players, vec!["alice", "bob"]);
+            assert_eq!(res.bracket[0].byes, vec!["carol"]);
+
+            play(&mut app, "cup round 1 game 1", [("alice", 1), ("bob", 2)]);
+            let res = tournament(&app);
+            assert_eq!(res.bracket[0].games[0].winner, Some("bob".to_string()));
+            assert_eq!(res.bracket[1].games[0].players, vec!["bob", "carol"]);
+            assert_eq!(res.tournament.winner, None);
+
+            let final_name = res.bracket[1].games[0].name.clone();
+            assert_eq!(final_name, "cup round 2 game 1 #2");
+            play(&mut app, &final_name, [("bob", 1), ("carol", 1)]);
+            let res = tournament(&app);
+            assert_eq!(res.tournament.winner, Some("carol".to_string()));
+            let claimable: ClaimableResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::Claimable {
+                        address: "carol".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(claimable.balances, vec![coin(30, NATIVE_DENOM)]);
+        }
+
+        #[test]
+        fn tournament_games_end_only_with_a_winner() {
+            let (mut app, contract) = proper_instantiate();
+            execute_as(
+                &mut app,
+                &contract,
+                "dave",
+                ExecuteMsg::CreateTournament {
+                    name: "cup".to_string(),
+                    entry_fee: None,
+                    game_size: 2,
+                    num_of_adventures: 1,
+                    scoring_rule: None,
+                },
+            )
+            .unwrap();
+            for player in &PLAYERS[..2] {
+                execute_as(
+                    &mut app,
+                    &contract,
+                    player,
+                    ExecuteMsg::JoinTournament { tournament_id: 0 },
+                )
+                .unwrap();
+            }
+            execute_as(
+                &mut app,
+                &contract,
+                "dave",
+                ExecuteMsg::StartTournament { tournament_id: 0 },
+            )
+            .unwrap();
+
+            // Entrants cannot end their own game, and a game where nobody won
+            // an adventure has nobody to advance
+            let name = "cup round 1 game 1".to_string();
+            let end = ExecuteMsg::EndGame { name: name.clone() };
+            let err = execute_as(&mut app, &contract, "alice", end.clone()).unwrap_err();
+            assert!(err.contains("Only the host"));
+            for msg in [
+                ExecuteMsg::OpenAdventure {
+                    name: name.clone(),
+                    adventure_number: 0,
+                    content: None,
+                },
+                ExecuteMsg::GameAdventureStop {
+                    name: name.clone(),
+                    adventure_number: 0,
+                },
+            ] {
+                execute_as(&mut app, &contract, "dave", msg).unwrap();
+            }
+            let err = execute_as(&mut app, &contract, "dave", end).unwrap_err();
+            assert!(err.contains("once an adventure has a winner"));
+            assert!(!load_game(&app, &contract, &name).ended);
+        }
+
+        #[test]
+        fn cancelled_tournaments_refund_their_entrants() {
+            let (mut app, contract) = proper_instantiate();
+            let entry_fee = coin(10, NATIVE_DENOM);
+            execute_as(
+                &mut app,
+                &contract,
+                "dave",
+                ExecuteMsg::CreateTournament {
+                    name: "cup".to_string(),
+                    entry_fee: Some(entry_fee.clone()),
+                    game_size: 2,
+                    num_of_adventures: 1,
+                    scoring_rule: None,
+                },
+            )
+            .unwrap();
+            for player in &PLAYERS[..2] {
+                app.execute_contract(
+                    Addr::unchecked(*player),
+                    contract.addr(),
+                    &ExecuteMsg::JoinTournament { tournament_id: 0 },
+                    std::slice::from_ref(&entry_fee),
+                )
+                .unwrap();
+            }
+
+            let cancel = ExecuteMsg::CancelTournament { tournament_id: 0 };
+            execute_as(&mut app, &contract, "alice", cancel.clone()).unwrap_err();
+            execute_as(&mut app, &contract, ADMIN, cancel.clone()).unwrap();
+            let err = execute_as(&mut app, &contract, "dave", cancel).unwrap_err();
+            assert!(err.contains("cancelled"));
+            execute_as(
+                &mut app,
+                &contract,
+                "dave",
+                ExecuteMsg::StartTournament { tournament_id: 0 },
+            )
+            .unwrap_err();
+
+            for player in &PLAYERS[..2] {
+                execute_as(&mut app, &contract, player, ExecuteMsg::Claim {}).unwrap();
+                let balance = app.wrap().query_balance(*player, NATIVE_DENOM).unwrap();
+                assert_eq!(balance.amount, Uint128::new(1000));
+            }
+        }
+
+        #[test]
+        fn teams_vote_and_win_as_factions() {
+            let (mut app, contract) = proper_instantiate();
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame { teams, team, .. } = &mut init_msg {
+                *teams = Some(vec!["red".to_string(), "blue".to_string()]);
+                *team = Some("red".to_string());
+            }
+            execute(&mut app, &contract, init_msg);
+            let join = |player: &str, team: Option<&str>| ExecuteMsg::AddGamePlayer {
+                name: "movie".to_string(),
+                player: player.to_string(),
+                game_stake: 100,
+                join_code: None,
+                team: team.map(str::to_string),
+            };
+            let err = execute_as(&mut app, &contract, USER, join("bob", None)).unwrap_err();
+            assert_eq!(
+                err,
+                "Generic error: A team must be chosen to join this game."
+            );
+            execute_as(&mut app, &contract, USER, join("bob", Some("green"))).unwrap_err();
+            for (player, team) in [("bob", "red"), ("carol", "blue"), ("dave", "blue")] {
+                execute(&mut app, &contract, join(player, Some(team)));
+            }
+            execute(
+                &mut app,
+                &contract,
+                ExecuteMsg::StartGame {
+                    name: "movie".to_string(),
+                },
+            );
+
+            // Red picks option 1, blue is split and its tie goes to option 2,
+            // and option 2 wins the tie between the teams
+            open_adventure(&mut app, &contract, 0);
+            for (player, option) in [("alice", 1), ("bob", 1), ("carol", 2), ("dave", 1)] {
+                execute_as(&mut app, &contract, player, vote(player, option)).unwrap();
+            }
+            let res = stop_first_adventure(&mut app, &contract).unwrap();
+            let data: AdventureStopResponse = from_binary(&res.data.unwrap()).unwrap();
+            assert_eq!(data.winning_option, 2);
+            assert_eq!(data.winners, vec!["carol", "dave"]);
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(data.reward, game.adventure_funds / 2);
+
+            let res = execute_as(
+                &mut app,
+                &contract,
+                PLAYERS[0],
+                ExecuteMsg::EndGame {
+                    name: "movie".to_string(),
+                },
+            )
+            .unwrap();
+            let data: EndGameResponse = from_binary(&res.data.unwrap()).unwrap();
+            assert_eq!(data.winner, "blue");
+            let game = load_game(&app, &contract, "movie");
+            let carol = data
+                .standings
+                .iter()
+                .find(|standing| standing.player == "carol")
+                .unwrap();
+            assert_eq!(
+                carol.rewards,
+                game.adventure_rewards[0] + game.winning_reward / 2
+            );
+        }
+
+        #[test]
+        fn delegated_votes_count_for_the_delegate_and_reward_the_delegator() {
+            let (mut app, contract) = setup_game(init_game_msg());
+            let delegate = |to: &str| ExecuteMsg::DelegateVote {
+                name: "movie".to_string(),
+                to: to.to_string(),
+            };
+            let err = execute_as(&mut app, &contract, "bob", delegate("bob")).unwrap_err();
+            assert_eq!(
+                err,
+                "Generic error: Players cannot delegate their vote to themselves."
+            );
+            let res = execute_as(&mut app, &contract, "bob", delegate("carol")).unwrap();
+            assert_eq!(
+                event_attr(&res, "vote_delegated", "delegate"),
+                Some("carol".to_string())
+            );
+            // Delegated votes cannot be passed on
+            execute_as(&mut app, &contract, "carol", delegate("alice")).unwrap_err();
+            execute_as(&mut app, &contract, "alice", delegate("bob")).unwrap_err();
+
+            // Bob's vote follows carol, so option 1 beats the single vote for option 2
+            open_adventure(&mut app, &contract, 0);
+            execute_as(&mut app, &contract, "alice", vote("alice", 2)).unwrap();
+            execute_as(&mut app, &contract, "carol", vote("carol", 1)).unwrap();
+            let tally: AdventureTallyResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    contract.addr(),
+                    &QueryMsg::AdventureTally {
+                        name: "movie".to_string(),
+                        adventure_number: 0,
+                    },
+                )
+                .unwrap();
+            assert_eq!(tally.yet_to_vote, 0);
+            assert_eq!(
+                tally.tally,
+                vec![
+                    OptionTally {
+                        option: 1,
+                        votes: 1,
+                        weight: 2,
+                    },
+                    OptionTally {
+                        option: 2,
+                        votes: 1,
+                        weight: 1,
+                    },
+                ]
+            );
+
+            stop_first_adventure(&mut app, &contract).unwrap();
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(
+                game.adventure_winners,
+                vec![vec!["bob".to_string(), "carol".to_string()]]
+            );
+            assert_eq!(game.adventure_rewards, vec![120]);
+
+            let undelegate = ExecuteMsg::Undelegate {
+                name: "movie".to_string(),
+            };
+            execute_as(&mut app, &contract, "bob", undelegate.clone()).unwrap();
+            let err = execute_as(&mut app, &contract, "bob", undelegate).unwrap_err();
+            assert_eq!(
+                err,
+                "Generic error: This player has not delegated their vote."
+            );
+        }
+
+        #[test]
+        fn delegations_are_locked_while_votes_cannot_change() {
+            let mut init_msg = init_game_msg();
+            if let ExecuteMsg::InitGame {
+                allow_vote_change, ..
+            } = &mut init_msg
+            {
+                *allow_vote_change = Some(false);
+            }
+            let (mut app, contract) = setup_game(init_msg);
+            let delegate = |to: &str| ExecuteMsg::DelegateVote {
+                name: "movie".to_string(),
+                to: to.to_string(),
+            };
+            let undelegate = ExecuteMsg::Undelegate {
+                name: "movie".to_string(),
+            };
+            execute_as(&mut app, &contract, "bob", delegate("carol")).unwrap();
+
+            // Once carol has voted, bob cannot move his vote elsewhere
+            open_adventure(&mut app, &contract, 0);
+            execute_as(&mut app, &contract, "alice", vote("alice", 2)).unwrap();
+            execute_as(&mut app, &contract, "carol", vote("carol", 1)).unwrap();
+            for msg in [undelegate.clone(), delegate("alice")] {
+                let err = execute_as(&mut app, &contract, "bob", msg).unwrap_err();
+                assert!(err.contains("Delegations cannot change"));
+            }
+            stop_first_adventure(&mut app, &contract).unwrap();
+            let game = load_game(&app, &contract, "movie");
+            assert_eq!(game.adventure_winning_options, vec![1]);
 
-            let msg = ExecuteMsg::Increment {};
-            let cosmos_msg = cw_template_contract.call(msg).unwrap();
-            app.execute(Addr::unchecked(USER), cosmos_msg).unwrap();
+            execute_as(&mut app, &contract, "bob", undelegate).unwrap();
         }
     }
 }
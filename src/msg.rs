@@ -1,10 +1,19 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Coin, Timestamp};
+
+use crate::state::{
+    AdventureContent, Config, GameState, PlayerRating, PlayerStats, PrivateGameAccess, ScoringRule,
+    Season, StoryNode, StoryTemplate, StoryTemplateInfo, Tournament,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub count: i32,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 // #[cw_serde]
 // pub enum ExecuteMsg {
 //     Increment {},
@@ -17,35 +26,261 @@ pub struct GamePlayerVote {
     pub vote: u32,
 }
 
+/// Game actions emit a `wasm-moviemagic` event. Every event carries the
+/// `action`, `game` (name) and `game_id` attributes, plus:
+///
+/// - `game_created`: `host`, `stake`, `num_of_adventures`, `scoring_rule`
+/// - `player_joined`: `player`, `stake`, `team` (team games only)
+/// - `game_started`: `players`, `total_funds`, `adventure_funds`, `fee`,
+///   `season_pool`
+/// - `adventure_opened`: `adventure`
+/// - `vote_cast`: `adventure`, `player`, `vote` (option number or `abstain`)
+/// - `vote_delegated`: `player`, `delegate`
+/// - `vote_undelegated`: `player`, `delegate`
+/// - `adventure_closed`: `adventure`, `winning_option` (0 when void),
+///   `winners` (comma separated addresses, omitted when nobody won), `reward`
+/// - `game_ended`: `winner` (a team name in team games, omitted when nobody
+///   won), `reward`
+/// - `answer_committed`: `adventure`
+/// - `answer_revealed`: `adventure`, `option`
+/// - `player_removed`: `player`, `refund`
+/// - `player_banned`: `player`
+/// - `host_transferred`: `previous_host`, `new_host`
+///
+/// Tournament events carry `action` and `tournament_id` instead:
+///
+/// - `tournament_created`: `organizer`
+/// - `tournament_joined`: `player`
+/// - `round_started`: `round`, `players`
+/// - `tournament_won`: `winner`
+/// - `tournament_cancelled`: `refunded` (number of entrants)
+///
+/// Other actions add a `method` attribute to the wasm event.
 #[cw_serde]
 pub enum ExecuteMsg {
     InitGame {
         name: String,
         player: String,
         game_stake: u64,
-        num_of_adventures: u32,
+        // Required unless the game is created from a story template
+        num_of_adventures: Option<u32>,
+        // Defaults to majority-wins when omitted
+        scoring_rule: Option<ScoringRule>,
+        // Whether players may change their vote on an open adventure.
+        // Defaults to true; when false only the first choice counts.
+        allow_vote_change: Option<bool>,
+        // Branching story starting at node 0. The winning option of each
+        // adventure selects the next node, and the game ends when an ending is
+        // reached. num_of_adventures caps the length of the story.
+        story: Option<Vec<StoryNode>>,
+        // Creates the game from a story template. The template provides the
+        // story and the defaults for the settings above. Uses the latest
+        // version when no version is given.
+        template_id: Option<u64>,
+        template_version: Option<u32>,
+        // Exact amount every player, including the host, must send to join.
+        // game_stake must equal the buy-in amount.
+        buy_in: Option<Coin>,
+        // Players needed to start the game, defaults to 2
+        min_players: Option<u32>,
+        // Players allowed to join the game, defaults to the contract-wide cap
+        max_players: Option<u32>,
+        // Makes the game private. Private games are hidden from ListGames
+        // unless explicitly requested.
+        private_access: Option<PrivateGameAccess>,
+        // Plays the game in teams. The majority choice of each team is its
+        // vote, the scoring rule picks the winning teams, and the members of
+        // those teams who voted share the reward. The game is won by a team.
+        teams: Option<Vec<String>>,
+        // Team of the host, required in team games
+        team: Option<String>,
     },
     AddGamePlayer {
         name: String,
         player: String,
         game_stake: u64,
+        // Preimage of the join code hash of a private game
+        join_code: Option<String>,
+        // Team joined by the player, required in team games
+        team: Option<String>,
     },
     StartGame {
         name: String,
     },
+    // Host only. Opens the next adventure for voting. Adventures are
+    // played in order and only one can be open at a time. When content is
+    // attached, votes must pick one of its option labels (1-based).
+    OpenAdventure {
+        name: String,
+        adventure_number: u32,
+        content: Option<AdventureContent>,
+    },
+    // Sent by the player. Votes for an option of the open adventure.
     VoteForAdventure {
         name: String,
         player: String,
         adventure_number: u32,
         vote: u32,
     },
+    // Sent by the player. Excludes them from the tally and the reward of
+    // the adventure.
+    AbstainFromAdventure {
+        name: String,
+        player: String,
+        adventure_number: u32,
+    },
+    // Host only. Closes the open adventure and pays its winners. Host-judged
+    // adventures are closed by RevealAdventureAnswer instead.
     GameAdventureStop {
         name: String,
         adventure_number: u32,
     },
+    // Host only. Ends the game and pays out the pot once every adventure
+    // has been played.
     EndGame {
         name: String,
     },
+    // Hands the vote of the sender to another player of the game. The
+    // delegate's vote then also counts for the sender, who shares in the
+    // reward, in every adventure the sender does not vote in themselves.
+    // Games that disallow vote changes lock delegations while an adventure
+    // is open.
+    DelegateVote {
+        name: String,
+        to: String,
+    },
+    Undelegate {
+        name: String,
+    },
+    // Host only. Commits to the answer of a host-judged adventure before
+    // voting on it starts. The hash is the hex encoded sha256 of
+    // "<option>:<salt>".
+    CommitAdventureAnswer {
+        name: String,
+        adventure_number: u32,
+        answer_hash: String,
+    },
+    // Host only. Submits the winning option of the open host-judged adventure
+    // and closes it. The salt is required when an answer hash was committed
+    // for the adventure.
+    RevealAdventureAnswer {
+        name: String,
+        adventure_number: u32,
+        option: u32,
+        salt: Option<String>,
+    },
+    // Host only. Removes a player before the game starts and refunds their stake.
+    RemovePlayer {
+        name: String,
+        player: String,
+    },
+    // Host only. Prevents the address from joining the game, removing and
+    // refunding them first if they already joined.
+    BanPlayer {
+        name: String,
+        player: String,
+    },
+    // Host only. Hands the host role over to another player of the game.
+    TransferHost {
+        name: String,
+        new_host: String,
+    },
+    // Owner only. Proposes a new owner, who must accept before taking over.
+    ProposeOwner {
+        new_owner: String,
+    },
+    // Pending owner only. Completes the ownership transfer.
+    AcceptOwnership {},
+    // Owner only. Leaves the contract without an owner, permanently.
+    RenounceOwnership {},
+    // Owner only. Updates the contract-wide settings that are given.
+    UpdateConfig {
+        // Share of each game pot kept as a fee when the game starts, in
+        // basis points. Fees from buy-in games are sent to the owner.
+        fee_bps: Option<u16>,
+        max_players_per_game: Option<u32>,
+        allowed_denoms: Option<Vec<String>>,
+        // Share of each buy-in pot added to the pool of the running season,
+        // in basis points. Together with the fee it cannot exceed 10000.
+        season_pool_bps: Option<u16>,
+        // Share of the season pool paid to the first, second, ... player of
+        // the season leaderboard, in basis points. What is left goes to the
+        // owner.
+        season_payouts: Option<Vec<u16>>,
+    },
+    // Owner only. Sets or clears the guardian, who may pause the contract.
+    SetGuardian {
+        guardian: Option<String>,
+    },
+    // Owner or guardian. Blocks new games, joins, votes and every action
+    // that pays out, such as closing adventures and ending games. Claims and
+    // refunds keep working.
+    Pause {},
+    // Owner or guardian. Lifts the pause.
+    Unpause {},
+    // Owner only. Allows the address to publish story templates.
+    ApproveTemplateCreator {
+        address: String,
+    },
+    // Owner only. Revokes the permission to publish story templates.
+    RevokeTemplateCreator {
+        address: String,
+    },
+    // Owner or approved creators. Publishes a new story template, or a new
+    // version of an existing one when template_id is given.
+    PublishTemplate {
+        template_id: Option<u64>,
+        title: String,
+        story: Vec<StoryNode>,
+        num_of_adventures: u32,
+        scoring_rule: Option<ScoringRule>,
+        allow_vote_change: Option<bool>,
+        buy_in: Option<Coin>,
+    },
+    // Owner or template creator. Prevents new games from using the version.
+    DeprecateTemplate {
+        template_id: u64,
+        version: u32,
+    },
+    // Owner only. Schedules a season. Seasons are scheduled one at a time,
+    // once the previous season has ended, and cannot overlap. Games started
+    // between start_time and end_time count towards its leaderboard.
+    CreateSeason {
+        name: String,
+        start_time: Timestamp,
+        end_time: Timestamp,
+    },
+    // Owner only, or anyone once ownership has been renounced. Archives the
+    // standings of a season once it has ended.
+    // Games of the season that end afterwards no longer count towards it.
+    CloseSeason {
+        season_id: u64,
+    },
+    // Sends the game winnings and the season and tournament prizes of the sender
+    Claim {},
+    // Creates a knockout tournament organized and hosted by the sender.
+    // Entrants pay the entry fee to join, and the winner gets all the fees.
+    CreateTournament {
+        name: String,
+        entry_fee: Option<Coin>,
+        game_size: u32,
+        num_of_adventures: u32,
+        scoring_rule: Option<ScoringRule>,
+    },
+    // Registers the sender, who must send exactly the entry fee
+    JoinTournament {
+        tournament_id: u64,
+    },
+    // Organizer only. Creates the games of the first round. Later rounds
+    // are created when every game of the previous round has ended.
+    StartTournament {
+        tournament_id: u64,
+    },
+    // Organizer or owner only. Cancels a tournament that has not started and
+    // makes the entry fees claimable by the entrants.
+    CancelTournament {
+        tournament_id: u64,
+    },
 }
 
 #[cw_serde]
@@ -54,6 +289,106 @@ pub enum QueryMsg {
     // GetCount returns the current count as a json-encoded number
     #[returns(GetCountResponse)]
     GetCount {},
+    // Config returns the contract-wide settings, including the pending owner
+    #[returns(Config)]
+    Config {},
+    // Paused returns whether the contract is paused
+    #[returns(PausedResponse)]
+    Paused {},
+    // Game returns the full state of a single game, without the invite list
+    // and join code hash of private games
+    #[returns(GameState)]
+    Game { name: String },
+    // Adventure returns the content of an adventure along with its votes and results
+    #[returns(AdventureResponse)]
+    Adventure { name: String, adventure_number: u32 },
+    // AdventureTally returns the running counts of an adventure and the
+    // reward each winner would get if it closed now
+    #[returns(AdventureTallyResponse)]
+    AdventureTally { name: String, adventure_number: u32 },
+    // SimulatePayout returns the outcome of an open adventure if the player
+    // voted for the given option and it closed right after. Fails when the
+    // player could not cast that vote.
+    #[returns(SimulatePayoutResponse)]
+    SimulatePayout {
+        name: String,
+        adventure_number: u32,
+        player: String,
+        option: u32,
+    },
+    // StoryPath returns the story nodes played so far in a story game
+    #[returns(StoryPathResponse)]
+    StoryPath { name: String },
+    // ListGames returns a summary of the games, ordered by id. Private games
+    // are only included, and marked as private, when include_private is set.
+    // The rating bounds filter on the average rating of the players who
+    // joined the game.
+    #[returns(ListGamesResponse)]
+    ListGames {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        include_private: Option<bool>,
+        min_rating: Option<u32>,
+        max_rating: Option<u32>,
+    },
+    // PlayerGames returns the games the address is part of, ordered by id,
+    // optionally only those in the given phase
+    #[returns(PlayerGamesResponse)]
+    PlayerGames {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        phase: Option<GamePhase>,
+    },
+    // PlayerStats returns the results of the address across all games
+    #[returns(LeaderboardEntry)]
+    PlayerStats { address: String },
+    // PlayerRating returns the skill rating of the address, the initial
+    // rating if it has not finished a game yet
+    #[returns(PlayerRating)]
+    PlayerRating { address: String },
+    // Leaderboard returns the players ranked by games won (the default) or
+    // by total earnings, across all games or within a season. Paginate with
+    // the address of the last entry.
+    #[returns(LeaderboardResponse)]
+    Leaderboard {
+        sort_by: Option<LeaderboardSort>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        season_id: Option<u64>,
+    },
+    #[returns(Season)]
+    Season { season_id: u64 },
+    // Tournament returns the tournament along with its bracket
+    #[returns(TournamentResponse)]
+    Tournament { tournament_id: u64 },
+    #[returns(ListTournamentsResponse)]
+    ListTournaments {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // Claimable returns the game winnings and the season and tournament
+    // prizes the address can claim
+    #[returns(ClaimableResponse)]
+    Claimable { address: String },
+    // ListSeasons returns the seasons ordered by id
+    #[returns(ListSeasonsResponse)]
+    ListSeasons {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // Template returns a story template version, the latest one by default
+    #[returns(TemplateResponse)]
+    Template {
+        template_id: u64,
+        version: Option<u32>,
+    },
+    // ListTemplates returns the published story templates
+    #[returns(ListTemplatesResponse)]
+    ListTemplates {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 // We define a custom struct for each query response
@@ -61,3 +396,197 @@ pub enum QueryMsg {
 pub struct GetCountResponse {
     pub games_count: i32,
 }
+
+#[cw_serde]
+pub struct PausedResponse {
+    pub paused: bool,
+}
+
+#[cw_serde]
+pub struct AdventureResponse {
+    pub adventure_number: u32,
+    pub content: Option<AdventureContent>,
+    pub open: bool,
+    pub votes: Vec<GamePlayerVote>,
+    pub abstentions: Vec<String>,
+    pub winners: Option<Vec<String>>, // Set once the adventure is closed
+    pub reward: Option<u64>,          // Reward per winner once the adventure is closed
+}
+
+#[cw_serde]
+pub struct AdventureTallyResponse {
+    pub adventure_number: u32,
+    pub open: bool,
+    pub tally: Vec<OptionTally>, // Votes received by each option, in option order
+    pub abstentions: u32,
+    pub yet_to_vote: u32, // Players who have neither voted nor abstained
+    // Projection at the current counts. Hidden for host-judged adventures
+    // until the host reveals the answer.
+    pub projected_winning_option: Option<u32>,
+    pub projected_winners: u32,
+    pub projected_reward: Option<u64>, // Reward per winner
+}
+
+#[cw_serde]
+pub struct SimulatePayoutResponse {
+    // None for host-judged adventures until the host reveals the answer
+    pub winning_option: Option<u32>,
+    pub winners: u32,
+    pub reward: Option<u64>, // Reward per winner
+    pub payout: u64,         // Reward the player would receive
+}
+
+#[cw_serde]
+pub struct StoryStep {
+    pub adventure_number: u32,
+    pub node: u32,
+    pub winning_option: Option<u32>, // Set once the adventure is closed
+}
+
+#[cw_serde]
+pub struct StoryPathResponse {
+    pub steps: Vec<StoryStep>,
+    pub finished: bool,
+}
+
+#[cw_serde]
+pub struct TemplateResponse {
+    pub info: StoryTemplateInfo,
+    pub template: StoryTemplate,
+}
+
+#[cw_serde]
+pub struct ListTemplatesResponse {
+    pub templates: Vec<StoryTemplateInfo>,
+}
+
+#[cw_serde]
+pub struct ListSeasonsResponse {
+    pub seasons: Vec<Season>,
+}
+
+#[cw_serde]
+pub struct BracketGame {
+    pub game_id: u64,
+    pub name: String,
+    pub players: Vec<String>,
+    pub ended: bool,
+    pub winner: Option<String>, // Player advancing once the game has ended
+}
+
+#[cw_serde]
+pub struct BracketRound {
+    pub games: Vec<BracketGame>,
+    pub byes: Vec<String>,
+}
+
+#[cw_serde]
+pub struct TournamentResponse {
+    pub tournament: Tournament,
+    pub bracket: Vec<BracketRound>,
+}
+
+#[cw_serde]
+pub struct ListTournamentsResponse {
+    pub tournaments: Vec<Tournament>,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    pub balances: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct GameSummary {
+    pub id: u64,
+    pub name: String,
+    pub host: String,
+    pub num_of_players: u32,
+    pub max_players: u32,
+    pub buy_in: Option<Coin>,
+    pub started: bool,
+    pub ended: bool,
+    pub private: bool,
+}
+
+#[cw_serde]
+pub struct ListGamesResponse {
+    pub games: Vec<GameSummary>,
+}
+
+#[cw_serde]
+pub enum GamePhase {
+    Open,       // Waiting for players, not started yet
+    InProgress, // Started and not ended yet
+    Ended,
+}
+
+#[cw_serde]
+pub struct PlayerGameSummary {
+    pub game: GameSummary,
+    pub stake: u64,
+    pub votes: Vec<Option<u32>>, // Vote of the player in each adventure played so far
+    pub winnings: u64,           // Adventure rewards plus the winning reward
+}
+
+#[cw_serde]
+pub struct PlayerGamesResponse {
+    pub games: Vec<PlayerGameSummary>,
+}
+
+#[cw_serde]
+#[derive(Default, Copy)]
+pub enum LeaderboardSort {
+    #[default]
+    Wins,
+    Earnings,
+}
+
+#[cw_serde]
+pub struct LeaderboardEntry {
+    pub address: String,
+    pub stats: PlayerStats,
+    pub net_pnl: i64, // Total earned minus total staked
+}
+
+#[cw_serde]
+pub struct LeaderboardResponse {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+// Data returned by the execute messages, JSON encoded in Response::data
+#[cw_serde]
+pub struct InitGameResponse {
+    pub game_id: u64,
+}
+
+#[cw_serde]
+pub struct OptionTally {
+    pub option: u32,
+    pub votes: u32,
+    pub weight: u32, // Votes including those delegated to the voters
+}
+
+#[cw_serde]
+pub struct AdventureStopResponse {
+    pub adventure_number: u32,
+    pub tally: Vec<OptionTally>, // Votes received by each option, in option order
+    pub winning_option: u32,     // 0 when nobody voted and the adventure is void
+    pub winners: Vec<String>,
+    pub reward: u64,                          // Reward per winner
+    pub game_result: Option<EndGameResponse>, // Set when the adventure ended a story game
+}
+
+#[cw_serde]
+pub struct PlayerStanding {
+    pub player: String,
+    pub adventures_won: u32,
+    pub rewards: u64, // Adventure rewards plus the winning reward
+}
+
+#[cw_serde]
+pub struct EndGameResponse {
+    pub winner: String,
+    pub winning_reward: u64,
+    pub standings: Vec<PlayerStanding>, // Sorted by adventures won, winner first
+}
@@ -1,8 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, Coin, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
 
 use std::collections::HashMap;
 
@@ -16,6 +16,41 @@ pub struct AdventureState {
     pub votes: HashMap<String, u32>,
 }
 
+// Content shown to the players for a single adventure
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct AdventureContent {
+    pub prompt: String,               // Question or scene description
+    pub option_labels: Vec<String>,   // Label of option 1, option 2, ...
+    pub content_uri: Option<String>,  // Location of the media, e.g. ipfs://<cid>
+    pub content_hash: Option<String>, // Hash of the media for integrity checks
+}
+
+// A scene of a branching story. Option N of the scene leads to next[N - 1],
+// and None marks an ending.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StoryNode {
+    pub content: Option<AdventureContent>, // Content shown when the scene is opened
+    pub next: Vec<Option<u32>>,            // Node reached by each option
+}
+
+// Who may join a private game. A player may join if their address is on the
+// invite list or if they present the preimage of the join code hash.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PrivateGameAccess {
+    pub invite_list: Vec<String>,       // Addresses invited to the game
+    pub join_code_hash: Option<String>, // Hex encoded sha256 of the join code
+}
+
+// Rule used to pick the winning option of each adventure
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringRule {
+    #[default]
+    Majority, // The option with the most votes wins
+    Minority,   // The option with the fewest (non-zero) votes wins
+    HostJudged, // The option submitted by the host wins
+}
+
 // Define the state of the game
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct GameState {
@@ -33,13 +68,176 @@ pub struct GameState {
     pub adventure_rewards: Vec<u64>,
     pub adventure_winning_votes: Vec<u32>,
     pub num_of_adventures: u32, // Number of adventures
+    #[serde(default)]
+    pub scoring_rule: ScoringRule, // Rule used to decide each adventure
+    #[serde(default)]
+    pub host: String, // Address of the player who created the game
+    #[serde(default)]
+    pub adventure_answer_hashes: Vec<Option<String>>, // Answers committed by the host
+    #[serde(default)]
+    pub adventure_answers: Vec<Option<u32>>, // Answers revealed by the host
+    #[serde(default = "default_allow_vote_change")]
+    pub allow_vote_change: bool, // Whether players may change their vote
+    #[serde(default)]
+    pub adventure_abstentions: Vec<Vec<String>>, // Players who abstained from each adventure
+    #[serde(default)]
+    pub current_adventure: u32, // Adventure currently open, or the next one to open
+    #[serde(default)]
+    pub adventure_open: bool, // Flag to indicate if the current adventure is accepting votes
+    #[serde(default)]
+    pub adventure_contents: Vec<Option<AdventureContent>>, // Content attached when opening each adventure
+    #[serde(default)]
+    pub adventure_winning_options: Vec<u32>, // Winning option of each closed adventure
+    #[serde(default)]
+    pub story: Vec<StoryNode>, // Story graph, empty for games with a fixed number of adventures
+    #[serde(default)]
+    pub story_path: Vec<u32>, // Story node played by each adventure, in order
+    #[serde(default)]
+    pub template_id: Option<u64>, // Story template the game was created from
+    #[serde(default)]
+    pub template_version: Option<u32>, // Version of the story template
+    #[serde(default)]
+    pub buy_in: Option<Coin>, // Exact amount every player must send to join
+    #[serde(default = "default_min_players")]
+    pub min_players: u32, // Players required to start the game
+    #[serde(default = "default_max_players")]
+    pub max_players: u32, // Players allowed to join the game
+    #[serde(default)]
+    pub private_access: Option<PrivateGameAccess>, // Set for games that are not open to everyone
+    #[serde(default)]
+    pub id: u64, // Position of the game in the list of games
+    #[serde(default)]
+    pub player_stakes: HashMap<String, u64>, // Stake paid by each player
+    #[serde(default)]
+    pub banned_players: Vec<String>, // Addresses the host has banned from joining
+    #[serde(default)]
+    pub fee_amount: u64, // Fee taken from the pot when the game started
+    #[serde(default)]
+    pub season_id: Option<u64>, // Season that was running when the game started
+    #[serde(default)]
+    pub season_pool_amount: u64, // Share of the pot added to the season pool
+    #[serde(default)]
+    pub tournament_id: Option<u64>, // Tournament the game is part of
+    #[serde(default)]
+    pub teams: Vec<String>, // Team names, empty unless the game is played in teams
+    #[serde(default)]
+    pub player_teams: HashMap<String, String>, // Team chosen by each player
+    #[serde(default)]
+    pub delegations: HashMap<String, String>, // Player each delegator has handed their vote to
+}
+
+fn default_allow_vote_change() -> bool {
+    true
+}
+
+fn default_min_players() -> u32 {
+    2
+}
+
+fn default_max_players() -> u32 {
+    crate::contract::MAX_PLAYERS_PER_GAME
+}
+
+// A published story template that games can be created from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StoryTemplateInfo {
+    pub id: u64,
+    pub title: String,
+    pub creator: Addr,       // Address allowed to publish new versions
+    pub latest_version: u32, // Versions are numbered from 1
+}
+
+// A single version of a story template
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct StoryTemplate {
+    pub template_id: u64,
+    pub version: u32,
+    pub story: Vec<StoryNode>, // Story graph, including the content of every scene
+    pub num_of_adventures: u32, // Default cap on the number of adventures
+    pub scoring_rule: ScoringRule, // Default scoring rule
+    pub allow_vote_change: bool, // Default vote change policy
+    pub buy_in: Option<Coin>,  // Default buy-in
+    pub deprecated: bool,      // Deprecated versions cannot be used for new games
+}
+
+// Results of an address across all the games it finished
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, JsonSchema)]
+pub struct PlayerStats {
+    pub games_played: u32,
+    pub games_won: u32,
+    pub adventures_won: u32,
+    pub total_staked: u64, // Stakes paid into ended games
+    pub total_earned: u64, // Adventure and winning rewards
+}
+
+// A period during which started games count towards a separate leaderboard
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Season {
+    pub id: u64,
+    pub name: String,
+    pub start_time: Timestamp, // Games started from this time on belong to the season
+    pub end_time: Timestamp,   // Exclusive
+    pub closed: bool,          // Closed seasons have their standings archived
+    #[serde(default)]
+    pub pool: Vec<Coin>, // Prize pool collected from the buy-in games of the season
+}
+
+// Games of a single tournament round. Players left without an opponent
+// get a bye to the next round.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct TournamentRound {
+    pub games: Vec<u64>,
+    pub byes: Vec<String>,
+}
+
+// A knockout tournament where the winner of each game advances to the next
+// round until a single player is left
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Tournament {
+    pub id: u64,
+    pub name: String,
+    pub organizer: Addr,         // Hosts every game of the tournament
+    pub entry_fee: Option<Coin>, // Paid by each entrant, the winner takes all the fees
+    pub game_size: u32,          // Players per game
+    pub num_of_adventures: u32,
+    pub scoring_rule: ScoringRule,
+    pub entrants: Vec<String>,
+    pub rounds: Vec<TournamentRound>,
+    pub started: bool,
+    pub winner: Option<String>,
+    #[serde(default)]
+    pub cancelled: bool, // Cancelled before starting, the entry fees were refunded
+}
+
+// Skill rating of an address, updated every time it finishes a game
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct PlayerRating {
+    pub rating: u32,
+    pub games_rated: u32,
 }
 
 // Define the state of all the games
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
 pub struct MovieMagicContractState {
     pub games: Vec<GameState>, // Store multiple game instances
-    pub owner: Addr,           // Address of the contract owner
+}
+
+// Contract-wide settings managed by the owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct Config {
+    pub owner: Option<Addr>, // Address of the contract owner, None once renounced
+    pub pending_owner: Option<Addr>, // Proposed owner that has not accepted yet
+    pub fee_bps: u16,        // Share of each game pot kept as a fee, in basis points
+    pub max_players_per_game: u32, // Cap on max_players, at most MAX_PLAYERS_PER_GAME
+    pub allowed_denoms: Vec<String>, // Denoms accepted for buy-ins, any denom when empty
+    #[serde(default)]
+    pub guardian: Option<Addr>, // Address that may pause the contract besides the owner
+    #[serde(default)]
+    pub paused: bool, // Flag to block new games, joins, votes and payouts
+    #[serde(default)]
+    pub season_pool_bps: u16, // Share of each buy-in pot added to the season pool
+    #[serde(default)]
+    pub season_payouts: Vec<u16>, // Share of the pool paid to each rank, in basis points
 }
 
 #[derive(Serialize, Deserialize)]
@@ -49,3 +247,42 @@ pub struct QueryResp {
 
 // pub const STATE: Item<State> = Item::new("state");
 pub const STATE: Item<MovieMagicContractState> = Item::new("state");
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// Layout of the state before the owner moved to the config, read when
+// migrating older deployments
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema)]
+pub struct LegacyContractState {
+    pub owner: Addr, // Address of the contract owner
+}
+pub const LEGACY_STATE: Item<LegacyContractState> = Item::new("state");
+
+// Addresses approved by the owner to publish story templates
+pub const TEMPLATE_CREATORS: Map<&Addr, bool> = Map::new("template_creators");
+pub const TEMPLATE_COUNT: Item<u64> = Item::new("template_count");
+pub const TEMPLATES: Map<u64, StoryTemplateInfo> = Map::new("templates");
+pub const TEMPLATE_VERSIONS: Map<(u64, u32), StoryTemplate> = Map::new("template_versions");
+
+// Aggregate stats of every address that finished a game
+pub const PLAYER_STATS: Map<&Addr, PlayerStats> = Map::new("player_stats");
+
+pub const RATINGS: Map<&Addr, PlayerRating> = Map::new("ratings");
+
+pub const SEASON_COUNT: Item<u64> = Item::new("season_count");
+pub const SEASONS: Map<u64, Season> = Map::new("seasons");
+// Latest season. Seasons are scheduled one at a time, so it is the only one
+// games can count towards.
+pub const ACTIVE_SEASON: Item<Option<u64>> = Item::new("active_season");
+// Stats of the games that ended while their season was still open. They
+// no longer change once the season is closed.
+pub const SEASON_STATS: Map<(u64, &Addr), PlayerStats> = Map::new("season_stats");
+// Leaderboard indexes by board (criteria and season), rank key and address
+pub const RANKINGS: Map<(&str, u64, &Addr), ()> = Map::new("rankings");
+pub const TOURNAMENT_COUNT: Item<u64> = Item::new("tournament_count");
+pub const TOURNAMENTS: Map<u64, Tournament> = Map::new("tournaments");
+
+// Winnings and prizes waiting to be claimed, by address and denom
+pub const CLAIMABLE: Map<(&Addr, &str), Uint128> = Map::new("claimable");
+
+// Index of the games each player is part of, keyed by game id
+pub const PLAYER_GAMES: Map<(&Addr, u64), ()> = Map::new("player_games");